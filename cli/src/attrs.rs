@@ -5,8 +5,9 @@ use hdd::ata::data::attr::raw::Raw;
 use hdd::drivedb;
 use hdd::drivedb::vendor_attribute;
 
-use hdd::scsi::pages::{SCSIPages, ErrorCounter};
+use hdd::scsi::pages::{SCSIPages, ErrorCounter, SASPort, AttachedDevice, LinkRate, EnvironmentalSensor, EnvironmentalReport, EnvironmentalLimits};
 use hdd::scsi::SCSICommon;
+use hdd::scsi::defects::{Defects, List};
 use hdd::scsi::data::inquiry;
 
 use clap::{
@@ -405,6 +406,249 @@ fn print_human_scsi_error_counters(counters: &Vec<(&str, HashMap<ErrorCounter, u
 	table.printstd();
 }
 
+fn attached_device_to_string(dev: &AttachedDevice) -> String {
+	use self::AttachedDevice::*;
+	match *dev {
+		NoDevice => "no device".to_string(),
+		EndDevice => "end device".to_string(),
+		Expander => "expander".to_string(),
+		FanoutExpander => "fanout expander".to_string(),
+		Reserved(x) => format!("reserved ({})", x),
+	}
+}
+
+fn link_rate_to_string(rate: &LinkRate) -> String {
+	use self::LinkRate::*;
+	match *rate {
+		Unknown => "unknown".to_string(),
+		Disabled => "disabled".to_string(),
+		NegotiationFailed => "negotiation failed".to_string(),
+		SpinupHold => "spinup hold".to_string(),
+		PortSelector => "port selector".to_string(),
+		ResetInProgress => "reset in progress".to_string(),
+		UnsupportedPhy => "unsupported phy".to_string(),
+		Rate(x) => format!("{} Gbit/s", x),
+		Reserved(x) => format!("reserved ({})", x),
+	}
+}
+
+fn sas_ports_json(ports: &[SASPort]) -> serde_json::Value {
+	let ports: Vec<_> = ports.iter().map(|port| {
+		let phys: Vec<_> = port.phys.iter().map(|phy| {
+			let mut json = serde_json::Map::new();
+			json.insert("id".to_string(), phy.id.to_json().unwrap());
+			json.insert("sas-address".to_string(), format!("{:016x}", phy.sas_address).to_json().unwrap());
+			json.insert("attached-device".to_string(), attached_device_to_string(&phy.attached_device).to_json().unwrap());
+			json.insert("attached-sas-address".to_string(), format!("{:016x}", phy.attached_sas_address).to_json().unwrap());
+			json.insert("attached-phy-id".to_string(), phy.attached_phy_id.to_json().unwrap());
+			json.insert("link-rate".to_string(), match phy.link_rate {
+				LinkRate::Rate(x) => x.to_json().unwrap(),
+				ref x => link_rate_to_string(x).to_json().unwrap(),
+			});
+			json.insert("invalid-dwords".to_string(), phy.invalid_dwords.to_json().unwrap());
+			json.insert("running-disparity-errors".to_string(), phy.running_disparity_errors.to_json().unwrap());
+			json.insert("loss-of-dword-sync".to_string(), phy.loss_of_dword_sync.to_json().unwrap());
+			json.insert("phy-reset-problems".to_string(), phy.phy_reset_problems.to_json().unwrap());
+			json.to_json().unwrap()
+		}).collect();
+
+		let mut json = serde_json::Map::new();
+		json.insert("id".to_string(), port.id.to_json().unwrap());
+		json.insert("generation".to_string(), port.generation.to_json().unwrap());
+		json.insert("phys".to_string(), phys.to_json().unwrap());
+		json.to_json().unwrap()
+	}).collect();
+
+	ports.to_json().unwrap()
+}
+
+fn print_prom_sas_ports(labels: &HashMap<&str, String>, ports: &[SASPort]) {
+	for port in ports {
+		for phy in &port.phys {
+			let mut labels = labels.clone();
+			labels.insert("port", port.id.to_string());
+			labels.insert("phy", phy.id.to_string());
+			labels.insert("sas_address", format!("{:016x}", phy.sas_address));
+			labels.insert("attached_sas_address", format!("{:016x}", phy.attached_sas_address));
+
+			if let LinkRate::Rate(x) = phy.link_rate {
+				print!("{}\n", format_prom("scsi_sas_link_rate_gbps", &labels, x));
+			}
+			print!("{}\n", format_prom("scsi_sas_invalid_dwords", &labels, phy.invalid_dwords));
+			print!("{}\n", format_prom("scsi_sas_running_disparity_errors", &labels, phy.running_disparity_errors));
+			print!("{}\n", format_prom("scsi_sas_loss_of_dword_sync", &labels, phy.loss_of_dword_sync));
+			print!("{}\n", format_prom("scsi_sas_phy_reset_problems", &labels, phy.phy_reset_problems));
+		}
+	}
+}
+
+fn print_human_sas_ports(ports: &[SASPort]) {
+	let mut table = Table::new();
+	table.set_format(*prettytable::format::consts::FORMAT_CLEAN);
+
+	table.set_titles(Row::new(vec![
+		Cell::new("port"),
+		Cell::new("phy"),
+		Cell::new("SAS address"),
+		Cell::new("attached to"),
+		Cell::new("link rate"),
+		Cell::new("invalid DWORDs").style_spec("r"),
+		Cell::new("disparity errors").style_spec("r"),
+		Cell::new("DWORD sync losses").style_spec("r"),
+		Cell::new("reset problems").style_spec("r"),
+	]));
+
+	for port in ports {
+		for phy in &port.phys {
+			table.add_row(Row::new(vec![
+				Cell::new(&port.id.to_string()),
+				Cell::new(&phy.id.to_string()),
+				Cell::new(&format!("{:016x}", phy.sas_address)),
+				Cell::new(&match phy.attached_device {
+					AttachedDevice::NoDevice => "-".to_string(),
+					ref dev => format!("{} {:016x} (phy {})", attached_device_to_string(dev), phy.attached_sas_address, phy.attached_phy_id),
+				}),
+				Cell::new(&link_rate_to_string(&phy.link_rate)),
+				Cell::new(&phy.invalid_dwords.to_string()).style_spec("r"),
+				Cell::new(&phy.running_disparity_errors.to_string()).style_spec("r"),
+				Cell::new(&phy.loss_of_dword_sync.to_string()).style_spec("r"),
+				Cell::new(&phy.phy_reset_problems.to_string()).style_spec("r"),
+			]));
+		}
+	}
+
+	table.printstd();
+}
+
+/// Returns tuple of sensor's `(name, index, unit)`
+fn sensor_info(sensor: &EnvironmentalSensor) -> (&'static str, u16, &'static str) {
+	match *sensor {
+		EnvironmentalSensor::Temperature(i) => ("temperature", i as u16, "°C"),
+		EnvironmentalSensor::RelativeHumidity(i) => ("humidity", i as u16, "%"),
+		EnvironmentalSensor::Reserved(i) => ("reserved", i, ""),
+	}
+}
+
+fn environment_readings(report: &EnvironmentalReport) -> Vec<(&'static str, Option<i8>)> {
+	vec![
+		("current", report.current),
+		("lifetime-max", report.lifetime_max),
+		("lifetime-min", report.lifetime_min),
+		("power-on-max", report.max_since_power_on),
+		("power-on-min", report.min_since_power_on),
+		("other-max", report.max_other),
+		("other-min", report.min_other),
+	]
+}
+
+fn environment_limits(limits: &EnvironmentalLimits) -> Vec<(&'static str, Option<i8>)> {
+	vec![
+		("high-critical-trigger", limits.high_critical_trigger),
+		("high-critical-reset", limits.high_critical_reset),
+		("low-critical-reset", limits.low_critical_reset),
+		("low-critical-trigger", limits.low_critical_trigger),
+		("high-operating-trigger", limits.high_operating_trigger),
+		("high-operating-reset", limits.high_operating_reset),
+		("low-operating-reset", limits.low_operating_reset),
+		("low-operating-trigger", limits.low_operating_trigger),
+	]
+}
+
+fn environment_json(reports: &[EnvironmentalReport], limits: &[EnvironmentalLimits]) -> serde_json::Value {
+	let mut sensors: Vec<_> = reports.iter().map(|r| r.sensor).collect();
+	for l in limits {
+		if !sensors.contains(&l.sensor) { sensors.push(l.sensor); }
+	}
+
+	let sensors: Vec<_> = sensors.iter().map(|sensor| {
+		let (name, index, _) = sensor_info(sensor);
+
+		let mut json = serde_json::Map::new();
+		json.insert("sensor".to_string(), name.to_json().unwrap());
+		json.insert("index".to_string(), index.to_json().unwrap());
+
+		if let Some(report) = reports.iter().find(|r| r.sensor == *sensor) {
+			for (key, value) in environment_readings(report) {
+				json.insert(key.to_string(), value.to_json().unwrap());
+			}
+		}
+
+		json.insert("limits".to_string(), match limits.iter().find(|l| l.sensor == *sensor) {
+			Some(limits) => {
+				let mut tmp = serde_json::Map::new();
+				for (key, value) in environment_limits(limits) {
+					tmp.insert(key.to_string(), value.to_json().unwrap());
+				}
+				tmp.to_json().unwrap()
+			},
+			None => serde_json::Value::Null,
+		});
+
+		json.to_json().unwrap()
+	}).collect();
+
+	sensors.to_json().unwrap()
+}
+
+fn print_prom_environment(labels: &HashMap<&str, String>, reports: &[EnvironmentalReport], limits: &[EnvironmentalLimits]) {
+	for report in reports {
+		let (name, index, _) = sensor_info(&report.sensor);
+		let mut labels = labels.clone();
+		labels.insert("sensor", name.to_string());
+		labels.insert("index", index.to_string());
+
+		for (key, value) in environment_readings(report) {
+			if let Some(value) = value {
+				labels.insert("reading", key.to_string());
+				print!("{}\n", format_prom("scsi_environment", &labels, value));
+			}
+		}
+	}
+
+	for limits in limits {
+		let (name, index, _) = sensor_info(&limits.sensor);
+		let mut labels = labels.clone();
+		labels.insert("sensor", name.to_string());
+		labels.insert("index", index.to_string());
+
+		for (key, value) in environment_limits(limits) {
+			if let Some(value) = value {
+				labels.insert("limit", key.to_string());
+				print!("{}\n", format_prom("scsi_environment_limit", &labels, value));
+			}
+		}
+	}
+}
+
+fn print_human_environment(reports: &[EnvironmentalReport], limits: &[EnvironmentalLimits]) {
+	let range = |min: Option<i8>, max: Option<i8>, unit: &str| match (min, max) {
+		(Some(min), Some(max)) => format!("{}..{}{}", min, max, unit),
+		(None, Some(max)) => format!("..{}{}", max, unit),
+		(Some(min), None) => format!("{}{}..", min, unit),
+		(None, None) => "-".to_string(),
+	};
+
+	for report in reports {
+		let (name, index, unit) = sensor_info(&report.sensor);
+		print!("  {} #{}: {}", name, index, match report.current {
+			Some(x) => format!("{}{}", x, unit),
+			None => "-".to_string(),
+		});
+		print!(" (since power-on: {}, lifetime: {})\n",
+			range(report.min_since_power_on, report.max_since_power_on, unit),
+			range(report.lifetime_min, report.lifetime_max, unit),
+		);
+	}
+
+	for limits in limits {
+		let (name, index, unit) = sensor_info(&limits.sensor);
+		print!("  {} #{} limits: operating {}, critical {}\n", name, index,
+			range(limits.low_operating_trigger, limits.high_operating_trigger, unit),
+			range(limits.low_critical_trigger, limits.high_critical_trigger, unit),
+		);
+	}
+}
+
 // TODO other formats
 // TODO prometheus: device id labels, just like in attrs_ata
 fn attrs_scsi(path: &str, dev: &DeviceArgument, format: Format) {
@@ -477,6 +721,23 @@ fn attrs_scsi(path: &str, dev: &DeviceArgument, format: Format) {
 		}
 	}
 
+	// Grown defect list
+
+	// also TODO Err()
+	if let Ok(x) = dev.defect_count(List::Grown) {
+		match format {
+			Prometheus => {
+				print!("{}\n", format_prom("scsi_grown_defects", &labels, x));
+			},
+			Plain => {
+				print!("\nElements in grown defect list: {}\n", x);
+			},
+			JSON => {
+				json.insert("grown-defects".to_string(), x.to_json().unwrap());
+			},
+		}
+	}
+
 	// Temperature
 
 	// also TODO Err()
@@ -504,6 +765,46 @@ fn attrs_scsi(path: &str, dev: &DeviceArgument, format: Format) {
 		}
 	}
 
+	// Environmental reports and limits
+
+	// also TODO Err()
+	let env_reports = pages.environmental_reporting().unwrap_or(vec![]);
+	let env_limits = pages.environmental_limits().unwrap_or(vec![]);
+	if !env_reports.is_empty() || !env_limits.is_empty() {
+		match format {
+			Prometheus => {
+				print_prom_environment(&labels, &env_reports, &env_limits);
+			},
+			Plain => {
+				print!("\nEnvironment:\n");
+				print_human_environment(&env_reports, &env_limits);
+			},
+			JSON => {
+				json.insert("environment".to_string(), environment_json(&env_reports, &env_limits));
+			},
+		}
+	}
+
+	// SAS phys
+
+	// also TODO Err()
+	if let Ok(ports) = pages.sas_ports() {
+		match format {
+			Prometheus => {
+				print_prom_sas_ports(&labels, &ports);
+			},
+			Plain => {
+				if !ports.is_empty() {
+					print!("\nSAS phys:\n");
+					print_human_sas_ports(&ports);
+				}
+			},
+			JSON => {
+				json.insert("sas-ports".to_string(), sas_ports_json(&ports));
+			},
+		}
+	}
+
 	// Start-Stop Cycle Counters
 
 	// also TODO Err()
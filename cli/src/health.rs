@@ -1,4 +1,6 @@
-use hdd::ata::misc::Misc;
+use hdd::health::Health;
+use hdd::scsi::pages::SCSIPages;
+use hdd::scsi::data::sense;
 
 use clap::{
 	App,
@@ -9,7 +11,7 @@ use clap::{
 use serde_json;
 use serde_json::value::ToJson;
 
-use super::{DeviceArgument, when_smart_enabled, arg_json};
+use super::{DeviceArgument, when_smart_enabled, smart_status, arg_json};
 
 pub fn subcommand() -> App<'static, 'static> {
 	SubCommand::with_name("health")
@@ -17,36 +19,56 @@ pub fn subcommand() -> App<'static, 'static> {
 		.arg(arg_json())
 }
 
+/// `reason` is the additional sense code of the informational exception that SCSI device reports, if any
+fn print_health(status: Option<bool>, reason: Option<(u8, u8)>, use_json: bool) {
+	if use_json {
+		print!("{}\n", serde_json::to_string(&status.to_json().unwrap()).unwrap());
+	} else {
+		print!("S.M.A.R.T. health status: {}\n", match status {
+			Some(true) => "good",
+			Some(false) => "BAD",
+			None => "(unknown)",
+		});
+
+		// for SCSI devices, tell why the device is deemed failing
+		if let Some((asc, ascq)) = reason {
+			print!("Reason: {}\n", sense::key::decode_asc(asc, ascq)
+				.map(|x| x.to_string())
+				.unwrap_or_else(|| format!("unknown additional sense code: {:02x} {:02x}", asc, ascq)));
+		}
+	}
+}
+
 pub fn health(
 	_: &str,
 	dev: &DeviceArgument,
 	args: &ArgMatches,
 ) {
-	let id = match *dev {
-		#[cfg(not(target_os = "linux"))]
-		DeviceArgument::ATA(_, ref id) => id,
-		DeviceArgument::SAT(_, ref id) => id,
-		DeviceArgument::SCSI(_) => unimplemented!(),
-	};
-
 	let use_json = args.is_present("json");
 
-	when_smart_enabled(&id.smart, "health status", || {
-		let status = match *dev {
-			#[cfg(not(target_os = "linux"))]
-			DeviceArgument::ATA(ref dev, _) => dev.get_smart_health().unwrap(),
-			DeviceArgument::SAT(ref dev, _) => dev.get_smart_health().unwrap(),
-			DeviceArgument::SCSI(_) => unimplemented!(),
-		};
-
-		if use_json {
-			print!("{}\n", serde_json::to_string(&status.to_json().unwrap()).unwrap());
-		} else {
-			print!("S.M.A.R.T. health status: {}\n", match status {
-				Some(true) => "good",
-				Some(false) => "BAD",
-				None => "(unknown)",
-			});
-		}
-	});
+	match *dev {
+		// SCSI devices still report informational exceptions on request (via log page or REQUEST SENSE) even if reporting is disabled (DEXCPT=1), so there's no S.M.A.R.T. status to check first
+		DeviceArgument::SCSI(ref scsidev) => {
+			// this is what `Health::get_health()` does as well, but we also need the code itself to tell why the device is failing
+			let code = match SCSIPages::new(scsidev).informational_exception_code() {
+				Ok(code) => code,
+				Err(err) => {
+					eprint!("Cannot read health status: {}\n", err);
+					None
+				},
+			};
+			let status = code.map(|code| code == (0, 0));
+			let reason = code.and_then(|code| if code != (0, 0) { Some(code) } else { None });
+			print_health(status, reason, use_json);
+		},
+		_ => when_smart_enabled(&smart_status(dev), "health status", || {
+			let status = match *dev {
+				#[cfg(not(target_os = "linux"))]
+				DeviceArgument::ATA(ref dev, _) => dev.get_health().unwrap(),
+				DeviceArgument::SAT(ref dev, _) => dev.get_health().unwrap(),
+				DeviceArgument::SCSI(_) => unreachable!(),
+			};
+			print_health(status, None, use_json);
+		}),
+	}
 }
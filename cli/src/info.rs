@@ -1,7 +1,10 @@
 use hdd::ata::data::id;
 use hdd::drivedb;
-use hdd::scsi::SCSICommon;
+use hdd::scsi::{SCSICommon, PageControl};
+use hdd::scsi::modes::{self, Modes};
 use hdd::scsi::data::inquiry;
+use hdd::scsi::data::vpd::block_device_characteristics::{BlockDeviceCharacteristics, FormFactor};
+use hdd::scsi::data::mode_page::informational_exceptions::InformationalExceptions;
 
 use clap::{
 	ArgMatches,
@@ -22,6 +25,25 @@ fn bool_to_sup(b: bool) -> &'static str {
 	else { "not supported" }
 }
 
+fn print_rpm(rpm: &id::RPM) {
+	match *rpm {
+		id::RPM::Unknown => (),
+		id::RPM::NonRotating => print!("RPM:      N/A (SSD or other non-rotating media)\n"),
+		id::RPM::RPM(i) => print!("RPM:      {}\n", i),
+	};
+}
+
+fn form_factor_to_string(form_factor: &FormFactor) -> Option<&'static str> {
+	match *form_factor {
+		FormFactor::Inch5_25 => Some("5.25 inches"),
+		FormFactor::Inch3_5 => Some("3.5 inches"),
+		FormFactor::Inch2_5 => Some("2.5 inches"),
+		FormFactor::Inch1_8 => Some("1.8 inches"),
+		FormFactor::LessThan1_8 => Some("less than 1.8 inches"),
+		FormFactor::NotReported | FormFactor::Reserved(_) => None,
+	}
+}
+
 fn print_ata_id(id: &id::Id, dbentry: &Option<drivedb::Match>) {
 	if id.incomplete { print!("WARNING: device reports information it provides is incomplete\n\n"); }
 
@@ -29,11 +51,7 @@ fn print_ata_id(id: &id::Id, dbentry: &Option<drivedb::Match>) {
 	// XXX we're skipping id.commands_supported for now as it is hardly of any interest to users
 
 	print!("Model:    {}\n", id.model);
-	match id.rpm {
-		id::RPM::Unknown => (),
-		id::RPM::NonRotating => print!("RPM:      N/A (SSD or other non-rotating media)\n"),
-		id::RPM::RPM(i) => print!("RPM:      {}\n", i),
-	};
+	print_rpm(&id.rpm);
 	print!("Firmware: {}\n", id.firmware);
 	print!("Serial:   {}\n", id.serial);
 	// TODO: id.wwn_supported is cool, but actual WWN ID is better
@@ -92,12 +110,57 @@ fn print_ata_id(id: &id::Id, dbentry: &Option<drivedb::Match>) {
 	print!("\n");
 }
 
-fn print_scsi_id(inquiry: &inquiry::Inquiry) {
+fn print_scsi_id(inquiry: &inquiry::Inquiry, serial: &Option<String>, lu_id: &Option<String>, characteristics: &Option<BlockDeviceCharacteristics>) {
 	print!("Vendor:   {}\n", inquiry.vendor_id);
 	print!("Model:    {}\n", inquiry.product_id);
+	if let Some(ref serial) = *serial {
+		print!("Serial:   {}\n", serial);
+	}
+	if let Some(ref lu_id) = *lu_id {
+		print!("LU ID:    {}\n", lu_id);
+	}
 	print!("Firmware: {}\n", inquiry.product_rev);
 
+	if let Some(ref characteristics) = *characteristics {
+		print_rpm(&characteristics.rpm);
+		if let Some(form_factor) = form_factor_to_string(&characteristics.form_factor) {
+			print!("Form factor: {}\n", form_factor);
+		}
+	}
+
+	let standards: Vec<_> = inquiry.version_descriptors.iter()
+		.filter_map(|v| v.standard)
+		.collect();
+	if !standards.is_empty() {
+		print!("Standards: {}\n", standards.join(", "));
+	}
+
 	// TODO other inquiry fields, capacity, …
+
+	print!("\n");
+}
+
+fn print_scsi_ie(status: &Option<id::Ternary>, ie: &Option<InformationalExceptions>) {
+	match *status {
+		Some(ref status) => print!("S.M.A.R.T. (informational exceptions): {}\n", status),
+		None => print!("S.M.A.R.T. (informational exceptions): (unknown)\n"),
+	}
+
+	if let Some(ref ie) = *ie {
+		print!("Warnings:         {}\n", if ie.ewasc { "enabled" } else { "disabled" });
+		print!("Performance mode: {}\n", if ie.perf { "enabled (no delays)" } else { "disabled" });
+		print!("Reporting method: {}\n", ie.mrie);
+		print!("Interval timer:   {}\n", match ie.interval_timer {
+			0 | 0xffff_ffff => "vendor-specific".to_string(),
+			x => format!("{:.1} s", x as f32 / 10.),
+		});
+		print!("Report count:     {}\n", match ie.report_count {
+			0 => "unlimited".to_string(),
+			x => x.to_string(),
+		});
+	}
+
+	print!("\n");
 }
 
 pub fn subcommand() -> App<'static, 'static> {
@@ -121,15 +184,43 @@ pub fn info(
 
 	let use_json = args.is_present("json");
 
-	if let DeviceArgument::SCSI(ref dev) = *dev {
-		let (_sense, data) = dev.scsi_inquiry(false, 0).unwrap();
+	if let DeviceArgument::SCSI(ref scsidev) = *dev {
+		let (_sense, data) = scsidev.scsi_inquiry(false, 0).unwrap();
 		let inquiry = inquiry::parse_inquiry(&data);
+		let serial = scsidev.unit_serial_number().ok();
+		let lu_id = scsidev.logical_unit_id().ok()
+			.and_then(|id| id)
+			.and_then(|id| id.canonical());
+		let characteristics = scsidev.block_device_characteristics().ok();
+
+		// `None` if it is not known whether device reports informational exceptions
+		let (smart, ie) = match scsidev.informational_exceptions_control(PageControl::Current) {
+			Ok(ie) => (Some(ie.status()), Some(ie)),
+			Err(modes::Error::NotSupported) => (Some(id::Ternary::Unsupported), None),
+			Err(err) => {
+				eprint!("Cannot read Informational Exceptions Control mode page: {}\n", err);
+				(None, None)
+			},
+		};
 
 		if use_json {
-			let info = inquiry.to_json().unwrap();
+			let mut info = inquiry.to_json().unwrap();
+
+			info.as_object_mut().unwrap().insert("serial".to_string(), serial.to_json().unwrap());
+			info.as_object_mut().unwrap().insert("lu_id".to_string(), lu_id.to_json().unwrap());
+			if let Some(characteristics) = characteristics {
+				info.as_object_mut().unwrap().insert("rpm".to_string(), characteristics.rpm.to_json().unwrap());
+				info.as_object_mut().unwrap().insert("form_factor".to_string(), characteristics.form_factor.to_json().unwrap());
+			}
+			info.as_object_mut().unwrap().insert("smart".to_string(), smart.to_json().unwrap());
+			if let Some(ie) = ie {
+				info.as_object_mut().unwrap().insert("informational_exceptions".to_string(), ie.to_json().unwrap());
+			}
+
 			print!("{}\n", serde_json::to_string(&info).unwrap());
 		} else {
-			print_scsi_id(&inquiry);
+			print_scsi_id(&inquiry, &serial, &lu_id, &characteristics);
+			print_scsi_ie(&smart, &ie);
 		}
 	}
 
@@ -0,0 +1,134 @@
+use hdd::scsi::pages::{SCSIPages, page_name};
+use hdd::scsi::data::log_page::{self, Value, DecodedParameter};
+
+use clap::{
+	App,
+	ArgMatches,
+	SubCommand,
+};
+
+use serde_json;
+use serde_json::value::ToJson;
+
+use super::{DeviceArgument, arg_json};
+
+pub fn subcommand() -> App<'static, 'static> {
+	SubCommand::with_name("logs")
+		.about("Dumps all log pages that the device supports (SCSI only)")
+		.arg(arg_json())
+}
+
+fn to_hex(data: &[u8]) -> String {
+	data.iter().map(|x| format!("{:02x}", x)).collect::<Vec<_>>().join(" ")
+}
+
+fn value_json(value: &Value) -> serde_json::Value {
+	match *value {
+		Value::Counter(x) => x.to_json().unwrap(),
+		Value::ASCII(ref s) => s.to_json().unwrap(),
+		Value::Binary(ref data) => to_hex(data).to_json().unwrap(),
+	}
+}
+
+fn page_json(page: &log_page::Page, name: Option<&str>, params: Option<Vec<DecodedParameter>>) -> serde_json::Value {
+	let mut json = serde_json::Map::new();
+	json.insert("page".to_string(), page.page.to_json().unwrap());
+	json.insert("subpage".to_string(), page.subpage.unwrap_or(0).to_json().unwrap());
+	json.insert("name".to_string(), name.to_json().unwrap());
+
+	match params {
+		Some(params) => {
+			let params: Vec<_> = params.iter().map(|param| {
+				let mut json = serde_json::Map::new();
+				json.insert("code".to_string(), param.code.to_json().unwrap());
+				json.insert("name".to_string(), param.name.to_json().unwrap());
+				json.insert("value".to_string(), value_json(&param.value));
+				json.to_json().unwrap()
+			}).collect();
+			json.insert("params".to_string(), params.to_json().unwrap());
+		},
+		// not a list of params
+		None => {
+			json.insert("data".to_string(), to_hex(&page.data).to_json().unwrap());
+		},
+	}
+
+	json.to_json().unwrap()
+}
+
+fn print_page(page: &log_page::Page, name: Option<&str>, params: Option<Vec<DecodedParameter>>) {
+	print!("=== [{:02x}/{:02x}] {} ===\n", page.page, page.subpage.unwrap_or(0),
+		name.unwrap_or_else(|| page_name(page.page)));
+
+	match params {
+		Some(params) => for param in params {
+			print!("{:04x} {}: {}\n", param.code, param.name.unwrap_or("?"), match param.value {
+				Value::Counter(x) => x.to_string(),
+				Value::ASCII(s) => format!("{:?}", s),
+				Value::Binary(data) => to_hex(&data),
+			});
+		},
+		None => print!("{}\n", to_hex(&page.data)),
+	}
+	print!("\n");
+}
+
+pub fn logs(
+	_: &str,
+	dev: &DeviceArgument,
+	args: &ArgMatches,
+) {
+	let use_json = args.is_present("json");
+
+	let dev = match *dev {
+		#[cfg(not(target_os = "linux"))]
+		DeviceArgument::ATA(_, _) | DeviceArgument::SAT(_, _) => {
+			eprint!("Log pages are only available for SCSI devices\n");
+			return;
+		},
+		#[cfg(target_os = "linux")]
+		DeviceArgument::SAT(_, _) => {
+			eprint!("Log pages are only available for SCSI devices\n");
+			return;
+		},
+		DeviceArgument::SCSI(ref dev) => dev,
+	};
+
+	let mut pages = SCSIPages::new(dev);
+
+	let supported = match pages.supported_pages() {
+		Ok(supported) => supported,
+		Err(err) => {
+			eprint!("Cannot read the list of supported log pages: {}\n", err);
+			return;
+		},
+	};
+
+	let mut json = vec![];
+	for (page, subpage) in supported {
+		// list of supported pages is of no interest here
+		if page == 0x00 { continue; }
+
+		let page = match pages.get_page(page, subpage) {
+			Ok(page) => page,
+			Err(err) => {
+				eprint!("Cannot read log page {:02x}/{:02x}: {}\n", page, subpage, err);
+				continue;
+			},
+		};
+
+		// TODO Err()
+		let name = pages.page_name(page.page, subpage).unwrap_or(None);
+		let params = pages.decode_page(&page).unwrap_or_else(|_| page.decode());
+
+		if use_json {
+			json.push(page_json(&page, name, params));
+		} else {
+			print_page(&page, name, params);
+		}
+	}
+
+	if use_json {
+		print!("{}\n", serde_json::to_string(&json).unwrap());
+	}
+}
@@ -16,7 +16,7 @@
 extern crate hdd;
 
 use hdd::{device, Device};
-use hdd::scsi::SCSIDevice;
+use hdd::scsi::{SCSIDevice, SCSICommon};
 use hdd::ata::ATADevice;
 
 use hdd::ata::data::id;
@@ -45,6 +45,8 @@ use env_logger::LogBuilder;
 mod info;
 mod health;
 mod attrs;
+mod logs;
+mod reservations;
 
 pub fn when_smart_enabled<F>(status: &id::Ternary, action_name: &str, mut action: F) where F: FnMut() -> () {
 	match *status {
@@ -54,6 +56,17 @@ pub fn when_smart_enabled<F>(status: &id::Ternary, action_name: &str, mut action
 	}
 }
 
+/// S.M.A.R.T. status for ATA devices
+pub fn smart_status(dev: &DeviceArgument) -> id::Ternary {
+	match *dev {
+		#[cfg(not(target_os = "linux"))]
+		DeviceArgument::ATA(_, ref id) => id.smart,
+		DeviceArgument::SAT(_, ref id) => id.smart,
+		// SCSI devices report their health regardless of informational exceptions status, see `health` subcommand
+		DeviceArgument::SCSI(_) => unreachable!(),
+	}
+}
+
 pub fn open_drivedb(option: Option<&str>) -> Option<Vec<drivedb::Entry>> {
 	let drivedb = match option {
 		Some(file) => drivedb::load(file).ok(), // .ok(): see below
@@ -136,6 +149,8 @@ fn main() {
 		.subcommand(health::subcommand())
 		.subcommand(info::subcommand())
 		.subcommand(attrs::subcommand())
+		.subcommand(logs::subcommand())
+		.subcommand(reservations::subcommand())
 		.arg(Arg::with_name("type")
 			.short("t")
 			.long("type")
@@ -181,6 +196,8 @@ fn main() {
 		("info", Some(args)) => (info::info, args),
 		("health", Some(args)) => (health::health, args),
 		("attrs", Some(args)) => (attrs::attrs, args),
+		("logs", Some(args)) => (logs::logs, args),
+		("reservations", Some(args)) => (reservations::reservations, args),
 		_ => unreachable!(),
 	};
 
@@ -188,15 +205,26 @@ fn main() {
 	Why do we issue ATA IDENTIFY DEVICE here?
 	- Device id is what every subcommand uses for one reason or the other, but usually to check whether some feature is supported and enabled.
 	- It allows us to distinguish between pure SCSI devices and ATA devices behind SAT by issuing ATA PASS-THROUGH and checking whether this command is supported.
+
+	SAT layers also report ATA Information VPD page, which already contains IDENTIFY DEVICE data, so we look there first: some HBAs log ATA PASS-THROUGH sent to SAS devices as an error.
 	*/
 
 	let dev = match dtype {
 		Type::Auto => {
 			match dev.get_type().unwrap() {
 				device::Type::SCSI => {
-					// check whether devices replies to ATA PASS-THROUGH
-					let satdev = ATADevice::new(SCSIDevice::new(dev));
-					match satdev.get_device_id() {
+					let scsidev = SCSIDevice::new(dev);
+					let sat_id = scsidev.sat_information().ok()
+						.and_then(|info| info)
+						.and_then(|info| info.id());
+
+					let satdev = ATADevice::new(scsidev);
+					let id = match sat_id {
+						Some(id) => Ok(id),
+						// check whether devices replies to ATA PASS-THROUGH
+						None => satdev.get_device_id(),
+					};
+					match id {
 						// this is really an ATA device
 						Ok(id) =>
 							DeviceArgument::SAT(satdev, id),
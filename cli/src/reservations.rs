@@ -0,0 +1,143 @@
+use hdd::scsi::reservations::{self, Reservations};
+use hdd::scsi::data::persistent_reserve::{ReservationType, TransportID, Registrant};
+
+use clap::{
+	App,
+	ArgMatches,
+	SubCommand,
+};
+
+use serde_json;
+use serde_json::value::ToJson;
+
+use super::{DeviceArgument, arg_json};
+
+pub fn subcommand() -> App<'static, 'static> {
+	SubCommand::with_name("reservations")
+		.about("Shows persistent reservations and registered initiators (SCSI only)")
+		.arg(arg_json())
+}
+
+fn type_to_string(t: &ReservationType) -> String {
+	use self::ReservationType::*;
+	match *t {
+		WriteExclusive => "Write Exclusive".to_string(),
+		ExclusiveAccess => "Exclusive Access".to_string(),
+		WriteExclusiveRegistrantsOnly => "Write Exclusive, Registrants Only".to_string(),
+		ExclusiveAccessRegistrantsOnly => "Exclusive Access, Registrants Only".to_string(),
+		WriteExclusiveAllRegistrants => "Write Exclusive, All Registrants".to_string(),
+		ExclusiveAccessAllRegistrants => "Exclusive Access, All Registrants".to_string(),
+		Reserved(x) => format!("reserved type {:x}h", x),
+	}
+}
+
+fn transport_id_to_string(id: &TransportID) -> String {
+	use self::TransportID::*;
+	match *id {
+		FibreChannel(name) => format!("FC 0x{:016x}", name),
+		IEEE1394(eui) => format!("IEEE 1394 0x{:016x}", eui),
+		SRP(ref id) => format!("SRP 0x{}", id.iter().map(|x| format!("{:02x}", x)).collect::<String>()),
+		ISCSI { ref name, isid: Some(ref isid) } => format!("iSCSI {},i,0x{}", name, isid),
+		ISCSI { ref name, isid: None } => format!("iSCSI {}", name),
+		SAS(addr) => format!("SAS 0x{:016x}", addr),
+		Other { protocol, format, .. } => format!("protocol {:x}h (format {})", protocol, format),
+	}
+}
+
+fn print_registrant(r: &Registrant) {
+	print!("  0x{:016x}  port {}{}  {}{}\n",
+		r.key,
+		r.relative_target_port,
+		if r.all_target_ports { " (all ports)" } else { "" },
+		r.transport_id.as_ref().map(transport_id_to_string).unwrap_or_else(|| "?".to_string()),
+		if r.reservation_holder {
+			format!("  [holder: {}]", type_to_string(&r.reservation_type))
+		} else { "".to_string() },
+	);
+}
+
+pub fn reservations(
+	_: &str,
+	dev: &DeviceArgument,
+	args: &ArgMatches,
+) {
+	let use_json = args.is_present("json");
+
+	let dev = match *dev {
+		#[cfg(not(target_os = "linux"))]
+		DeviceArgument::ATA(_, _) | DeviceArgument::SAT(_, _) => {
+			eprint!("Persistent reservations are only available for SCSI devices\n");
+			return;
+		},
+		#[cfg(target_os = "linux")]
+		DeviceArgument::SAT(_, _) => {
+			eprint!("Persistent reservations are only available for SCSI devices\n");
+			return;
+		},
+		DeviceArgument::SCSI(ref dev) => dev,
+	};
+
+	let reservation = match dev.reservation() {
+		Ok(reservation) => reservation,
+		Err(reservations::Error::NotSupported) => {
+			eprint!("Device does not support persistent reservations\n");
+			return;
+		},
+		Err(err) => {
+			eprint!("Cannot read persistent reservation: {}\n", err);
+			return;
+		},
+	};
+	let capabilities = dev.reservation_capabilities().ok();
+	// READ FULL STATUS is optional, so fall back to READ KEYS
+	let full_status = dev.full_status().ok();
+	let keys = if full_status.is_none() { dev.registered_keys().ok() } else { None };
+
+	if use_json {
+		let mut json = serde_json::Map::new();
+		json.insert("generation".to_string(), reservation.generation.to_json().unwrap());
+		json.insert("reservation".to_string(), reservation.reservation.to_json().unwrap());
+		json.insert("capabilities".to_string(), capabilities.to_json().unwrap());
+		if let Some(status) = full_status {
+			json.insert("registrants".to_string(), status.registrants.to_json().unwrap());
+		}
+		if let Some(keys) = keys {
+			json.insert("keys".to_string(), keys.keys.to_json().unwrap());
+		}
+		print!("{}\n", serde_json::to_string(&json).unwrap());
+		return;
+	}
+
+	print!("Generation: {}\n", reservation.generation);
+	match reservation.reservation {
+		Some(ref r) => print!("Reservation: {} by 0x{:016x}\n", type_to_string(&r.reservation_type), r.key),
+		None => print!("Reservation: none\n"),
+	}
+
+	match (full_status, keys) {
+		(Some(status), _) => {
+			print!("Registrants: {}\n", status.registrants.len());
+			for r in &status.registrants {
+				print_registrant(r);
+			}
+		},
+		(None, Some(keys)) => {
+			print!("Registered keys: {}\n", keys.keys.len());
+			for key in &keys.keys {
+				print!("  0x{:016x}\n", key);
+			}
+		},
+		(None, None) => print!("Registrants: unknown\n"),
+	}
+
+	if let Some(caps) = capabilities {
+		print!("Persist through power loss: {}\n", match (caps.ptpl_c, caps.ptpl_a) {
+			(false, _) => "not supported",
+			(true, false) => "supported, inactive",
+			(true, true) => "active",
+		});
+		if caps.tmv {
+			print!("Supported types: {}\n", caps.types.iter().map(type_to_string).collect::<Vec<_>>().join("; "));
+		}
+	}
+}
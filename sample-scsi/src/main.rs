@@ -16,9 +16,13 @@
 extern crate hdd;
 use hdd::Device;
 use hdd::scsi::{SCSIDevice, SCSICommon};
+use hdd::scsi::misc::Misc;
+use hdd::scsi::commands::Commands;
+use hdd::scsi::enclosure::EnclosureServices;
 use hdd::scsi::pages::{SCSIPages, page_name};
 use hdd::scsi::data::inquiry;
-use hdd::scsi::data::vpd::device_id;
+use hdd::scsi::data::vpd;
+use hdd::scsi::data::vpd::{device_id, extended_inquiry, mode_page_policy, ata_information, block_device_characteristics};
 
 #[macro_use]
 extern crate clap;
@@ -89,70 +93,121 @@ fn main() {
 	);
 
 	let data = query("Inquiry", &dev, false, 0, verbose);
-	print!("{:#?}\n", inquiry::parse_inquiry(&data));
+	let inquiry = inquiry::parse_inquiry(&data);
+	print!("{:#?}\n", inquiry);
+
+	print!("=== Sense ===\n");
+	match dev.get_sense() {
+		Ok(Some(sense)) => {
+			print!("{:?}\n", sense.code());
+			for d in sense.descriptors() {
+				print!("{:?}\n", d);
+			}
+		},
+		Ok(None) => print!("no sense\n"),
+		Err(err) => print!("{}\n", err),
+	}
 
 	let data = query("[00] Supported VPD pages", &dev, true, 0, verbose);
-	let len = data[3];
+	let supported = vpd::supported_pages(&data).unwrap_or_default();
 	print!("supported:");
-	for i in 0..len {
-		print!(" {:02x}", data[(i+4) as usize]);
+	for page in &supported {
+		print!(" {:02x}", page);
 	}
 	print!("\n");
 
+	if supported.contains(&0x80) {
+		let data = query("[80] Unit Serial Number", &dev, true, 0x80, verbose);
+		print!("{:?}\n", vpd::unit_serial_number(&data));
+	}
+
+	if supported.contains(&0x86) {
+		let data = query("[86] Extended INQUIRY Data", &dev, true, 0x86, verbose);
+		print!("{:#?}\n", extended_inquiry::parse(&data));
+	}
+
+	if supported.contains(&0x87) {
+		let data = query("[87] Mode Page Policy", &dev, true, 0x87, verbose);
+		print!("{:#?}\n", mode_page_policy::parse(&data));
+	}
+
+	if supported.contains(&0x89) {
+		let data = query("[89] ATA Information", &dev, true, 0x89, verbose);
+		print!("{:#?}\n", ata_information::parse(&data));
+	}
+
+	if supported.contains(&0xb1) {
+		let data = query("[B1] Block Device Characteristics", &dev, true, 0xb1, verbose);
+		print!("{:#?}\n", block_device_characteristics::parse(&data));
+	}
+
 	let data = query("[83] Device Information", &dev, true, 0x83, verbose);
-	let len = ((data[2] as usize) << 8) + (data[3] as usize);
+	let descriptors = device_id::parse(&data).unwrap_or_default();
 
 	print!("descriptors:\n");
-	for d in device_id::parse(&data[4 .. 4+len]) {
+	for d in &descriptors {
 		print!("{:?}\n", d);
+		if let Some(id) = d.id.canonical() {
+			print!(">>> {}\n", id);
+		}
+	}
+	print!("logical unit: {:?}\n", device_id::logical_unit_id(&descriptors).and_then(|id| id.canonical()));
 
-		// TODO? from_utf8 it right in hdd::data::vpd::device_id
-		if d.codeset == device_id::CodeSet::ASCII {
-			match d.id {
-				device_id::Identifier::VendorSpecific(i) |
-				device_id::Identifier::FCNameIdentifier(i) => {
-					print!(">>> {:?}\n", std::str::from_utf8(i));
-				},
-				device_id::Identifier::Generic { vendor_id: v, id: i } => {
-					print!(">>> {:?}\n", std::str::from_utf8(v));
-					print!(">>> {:?}\n", std::str::from_utf8(i));
-				},
-				_ => (),
-			}
+	print!("=== Logical units ===\n");
+	print!("{:#?}\n", dev.luns(true));
+
+	print!("=== Supported commands ===\n");
+	match dev.supported_commands() {
+		Ok(commands) => for cmd in commands {
+			print!("{:02x}/{:?}: cdb_len={} timeouts={:?}\n", cmd.opcode, cmd.service_action, cmd.cdb_len, cmd.timeouts);
+		},
+		Err(err) => print!("{}\n", err),
+	}
+
+	print!("=== Task management functions ===\n");
+	print!("{:#?}\n", dev.task_management_functions());
+
+	if inquiry.enclosure_services || inquiry.device_type == inquiry::DeviceType::EnclosureServices {
+		print!("=== Enclosure ===\n");
+		print!("{:#?}\n", dev.ses_configuration());
+		match dev.slots() {
+			Ok(slots) => for slot in slots {
+				print!("{:?} {:?} {:?}: {:?}\n", slot.slot, slot.descriptor, slot.status.status, slot.sas_addresses);
+			},
+			Err(err) => print!("{}\n", err),
 		}
 	}
 
 	let mut pages = SCSIPages::new(&dev);
 	if let Ok(supported_pages) = pages.supported_pages() {
-		for p in supported_pages {
-			if p == 00 { continue; }
+		for (p, sp) in supported_pages {
+			if p == 0x00 { continue; }
 
-			print!("=== [{:02x}] {} ===\n", p, page_name(p));
-			match p {
+			let name = pages.page_name(p, sp).ok().and_then(|x| x).unwrap_or_else(|| page_name(p));
+			print!("=== [{:02x}/{:02x}] {} ===\n", p, sp, name);
+			match (p, sp) {
 				// already in cli
-				0x02...0x06 | 0x0d | 0x0e => (),
-				0x10 => print!("{:#?}\n", pages.self_test_results()),
-				0x2f => print!("{:#?}\n", pages.informational_exceptions()),
-				_ => (),
+				(0x02...0x06, 0x00) | (0x0d, 0x00) | (0x0e, 0x00) => (),
+				(0x10, 0x00) => print!("{:#?}\n", pages.self_test_results()),
+				(0x0e, 0x01) => print!("{:#?}\n", pages.utilization()),
+				(0x11, 0x00) => print!("{:#?}\n", pages.percentage_used_endurance()),
+				(0x15, 0x00) => print!("{:#?}\n", pages.background_scan()),
+				(0x15, 0x01) => print!("{:#?}\n", pages.pending_defects()),
+				(0x18, 0x00) => print!("{:#?}\n", pages.sas_ports()),
+				(0x19, 0x00) => print!("{:#?}\n", pages.general_statistics()),
+				(0x1a, 0x00) => print!("{:#?}\n", pages.power_condition_transitions()),
+				(0x2f, 0x00) => print!("{:#?}\n", pages.informational_exceptions()),
+				_ => {
+					let page = pages.get_page(p, sp);
+					if verbose {
+						if let Ok(ref page) = page {
+							print!("data: len={}", page.data.len());
+							print_hex(&page.data);
+						}
+					}
+					print!("{:#?}\n", page.map(|page| pages.decode_page(&page)));
+				},
 			}
 		}
 	};
-
-	/*
-	// TODO tell whether subpages are supported at all
-	let data = ask_log("[00/ff] Supported Log Pages/Subpages", &dev, 0x00, 0xff, verbose);
-	let page = log_page::parse(&data);
-	if let Some(page) = page {
-		for psp in page.data[..].chunks(2) {
-			let (page, subpage) = (psp[0], psp[1]);
-
-			let data = ask_log(&format!("[{:02x}/{:02x}] ?", page, subpage), &dev, page, subpage, verbose);
-			let page = log_page::parse(&data);
-			if let Some(page) = page {
-				print!("{:?}\n", page);
-				print!("{:#?}\n", page.parse_params());
-			}
-		}
-	}
-	*/
 }
@@ -29,12 +29,24 @@ impl fmt::Display for Ternary {
 	}
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(feature = "serializable", derive(Serialize))]
 pub enum RPM {
 	Unknown, NonRotating, RPM(u16)
 }
 
+impl RPM {
+	/// Interprets nominal media rotation rate, as reported by ATA IDENTIFY DEVICE word 217 or by SCSI Block Device Characteristics VPD page.
+	pub fn from(rate: u16) -> Self {
+		match rate {
+			// 0x0000 means rate is not reported; 0x0002–0x0400 and 0xffff are reserved (TODO warning?)
+			0x0000 | 0xffff | 0x0002...0x0400 => RPM::Unknown,
+			0x0001 => RPM::NonRotating,
+			i => RPM::RPM(i),
+		}
+	}
+}
+
 #[derive(Debug)]
 #[cfg_attr(feature = "serializable", derive(Serialize))]
 pub struct IdCommands {
@@ -266,12 +278,7 @@ pub fn parse_id(data: &Vec<u8>) -> Id {
 		} else { 512 },
 		sector_size_log: sector_size_log,
 
-		rpm: match data[217] {
-			// all values except 0x0000 are reserved (TODO warning?)
-			0x0000 | 0xffff | 0x0002...0x0400 => RPM::Unknown,
-			0x0001 => RPM::NonRotating,
-			i => RPM::RPM(i),
-		},
+		rpm: RPM::from(data[217]),
 
 		trusted_computing_supported: is_set(data[48], 0),
 
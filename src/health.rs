@@ -0,0 +1,67 @@
+/*!
+Device health status, regardless of the device type.
+
+For ATA devices, this is the result of SMART RETURN STATUS command. For SCSI devices, this is based on informational exceptions reported by the device (see [`SCSIPages::informational_exception_code`](../scsi/pages/struct.SCSIPages.html#method.informational_exception_code)).
+
+## Example
+
+```ignore
+use hdd::health::Health;
+
+...
+
+println!("health status: {}", match dev.get_health().unwrap() {
+	Some(true) => "good",
+	Some(false) => "BAD",
+	None => "(unknown)",
+});
+```
+*/
+
+#[cfg(not(target_os = "linux"))]
+use Device;
+
+use ata::ATADevice;
+use ata::misc::{self, Misc};
+use scsi::SCSIDevice;
+use scsi::pages::{self, SCSIPages};
+
+quick_error! {
+	#[derive(Debug)]
+	pub enum Error {
+		ATA(err: misc::Error) {
+			from()
+			display("{}", err)
+		}
+		SCSI(err: pages::Error) {
+			from()
+			display("{}", err)
+		}
+	}
+}
+
+/// See [module documentation](index.html).
+pub trait Health {
+	/// Returns `Some(false)` if device can no longer be considered reliable, `Some(true)` if it is fine, or `None` if health status cannot be determined.
+	fn get_health(&self) -> Result<Option<bool>, Error>;
+}
+
+#[cfg(not(target_os = "linux"))]
+impl Health for ATADevice<Device> {
+	fn get_health(&self) -> Result<Option<bool>, Error> {
+		Ok(self.get_smart_health()?)
+	}
+}
+
+impl Health for ATADevice<SCSIDevice> {
+	fn get_health(&self) -> Result<Option<bool>, Error> {
+		Ok(self.get_smart_health()?)
+	}
+}
+
+impl Health for SCSIDevice {
+	fn get_health(&self) -> Result<Option<bool>, Error> {
+		let code = SCSIPages::new(self).informational_exception_code()?;
+		Ok(code.map(|code| code == (0, 0)))
+	}
+}
@@ -81,6 +81,8 @@ mod cam;
 pub mod ata;
 pub mod scsi;
 
+pub mod health;
+
 pub mod drivedb;
 
 mod utils;
@@ -0,0 +1,179 @@
+/*!
+Functions to find out which commands, task management functions and logical units device supports.
+
+## Example
+
+```ignore
+use hdd::Device;
+use hdd::scsi::SCSIDevice;
+use hdd::scsi::commands::Commands;
+
+...
+
+for cmd in dev.supported_commands().unwrap() {
+	print!("{:02x}/{:?}: {:?}\n", cmd.opcode, cmd.service_action, cmd.timeouts);
+}
+
+// use device-recommended timeouts from now on
+let timeouts = dev.recommended_timeouts().unwrap();
+dev.set_command_timeouts(timeouts);
+
+// does device support READ CAPACITY(16)?
+print!("{:?}\n", dev.is_command_supported(0x9e, Some(0x10)));
+```
+*/
+
+use std::collections::HashMap;
+use byteorder::{ReadBytesExt, BigEndian};
+
+use scsi;
+use scsi::{SCSIDevice, SCSICommon};
+use scsi::data::opcodes::{self, Command, CommandInfo, Support, TaskManagementFunctions};
+use scsi::data::luns::{self, LUN};
+
+quick_error! {
+	#[derive(Debug)]
+	pub enum Error {
+		NotSupported {}
+		SCSI(err: scsi::Error) {
+			from()
+			display("{}", err)
+		}
+		/// failed to parse command response
+		InvalidData(what: &'static str) {
+			display("Unable to {}", what)
+		}
+	}
+}
+
+fn into_error(err: scsi::Error) -> Error {
+	if err.is_invalid_opcode() || err.is_invalid_field_in_cdb() {
+		Error::NotSupported
+	} else {
+		Error::SCSI(err)
+	}
+}
+
+fn is_invalid_field(err: &Error) -> bool {
+	match *err {
+		Error::NotSupported => true,
+		_ => false,
+	}
+}
+
+/// Returns list length from the first 4 bytes of the response, which is how both REPORT LUNS and REPORT SUPPORTED OPERATION CODES start.
+fn list_len(data: &[u8]) -> Option<u32> {
+	if data.len() < 4 { return None; }
+	Some((&data[0..4]).read_u32::<BigEndian>().unwrap())
+}
+
+/// Initial allocation length for the commands that return lists of unknown length; commands are repeated with larger buffer if the list does not fit
+const ALLOC: u32 = 4096;
+
+/// See [module documentation](index.html).
+pub trait Commands: SCSICommon {
+	/// Returns logical units that device can address, including well known logical units (e.g. REPORT LUNS logical unit) if `all` is set.
+	fn luns(&self, all: bool) -> Result<Vec<LUN>, Error> {
+		info!("querying logical units");
+
+		let select_report = if all { 0x02 } else { 0x00 };
+		let query = |alloc| -> Result<Vec<u8>, Error> {
+			let (sense, data) = self.report_luns(select_report, alloc)?;
+			scsi::check_sense(&sense).map_err(into_error)?;
+			Ok(data)
+		};
+
+		let data = query(ALLOC)?;
+		let data = match list_len(&data) {
+			// list is longer than the buffer
+			Some(len) if len.saturating_add(8) > ALLOC => query(len.saturating_add(8))?,
+			_ => data,
+		};
+
+		luns::parse(&data).ok_or(Error::InvalidData("parse REPORT LUNS response"))
+	}
+
+	/// Returns all the commands that device supports, with command timeouts if device reports them.
+	fn supported_commands(&self) -> Result<Vec<Command>, Error> {
+		info!("querying supported commands");
+
+		let query = |rctd, alloc| -> Result<Vec<u8>, Error> {
+			let (sense, data) = self.report_supported_opcodes(rctd, 0b000, 0, 0, alloc)?;
+			scsi::check_sense(&sense).map_err(into_error)?;
+			Ok(data)
+		};
+
+		// some devices do not support command timeouts descriptors and reject RCTD bit
+		let (rctd, data) = match query(true, ALLOC) {
+			Err(ref err) if is_invalid_field(err) => {
+				info!("REPORT SUPPORTED OPERATION CODES does not support RCTD, retrying without command timeouts");
+				(false, query(false, ALLOC)?)
+			},
+			Ok(data) => (true, data),
+			Err(err) => return Err(err),
+		};
+
+		let data = match list_len(&data) {
+			// list is longer than the buffer
+			Some(len) if len.saturating_add(4) > ALLOC => query(rctd, len.saturating_add(4))?,
+			_ => data,
+		};
+
+		opcodes::parse_all(&data).ok_or(Error::InvalidData("parse REPORT SUPPORTED OPERATION CODES response"))
+	}
+
+	/// Describes command `opcode` (with service action `service_action`, if the command has any): whether it is supported, CDB usage map and command timeouts.
+	fn command_info(&self, opcode: u8, service_action: Option<u16>) -> Result<CommandInfo, Error> {
+		info!("querying command info: opcode={:02x} service_action={:?}", opcode, service_action);
+
+		let (options, sa) = match service_action {
+			Some(sa) => (0b010, sa),
+			None => (0b001, 0),
+		};
+
+		let query = |rctd| -> Result<Vec<u8>, Error> {
+			let (sense, data) = self.report_supported_opcodes(rctd, options, opcode, sa, 512)?;
+			scsi::check_sense(&sense).map_err(into_error)?;
+			Ok(data)
+		};
+
+		let data = match query(true) {
+			Err(ref err) if is_invalid_field(err) => query(false)?,
+			ret => ret?,
+		};
+
+		opcodes::parse_one(&data).ok_or(Error::InvalidData("parse REPORT SUPPORTED OPERATION CODES response"))
+	}
+
+	/// Tells whether device supports command `opcode` (with service action `service_action`, if the command has any); returns `None` if device cannot tell.
+	fn is_command_supported(&self, opcode: u8, service_action: Option<u16>) -> Result<Option<bool>, Error> {
+		Ok(match self.command_info(opcode, service_action)?.support {
+			Support::Supported | Support::VendorSpecific => Some(true),
+			Support::NotSupported => Some(false),
+			Support::NotAvailable | Support::Reserved(_) => None,
+		})
+	}
+
+	fn task_management_functions(&self) -> Result<TaskManagementFunctions, Error> {
+		info!("querying supported task management functions");
+
+		let (sense, data) = self.report_supported_tmf(false, 16)?;
+		scsi::check_sense(&sense).map_err(into_error)?;
+
+		opcodes::parse_task_management(&data).ok_or(Error::InvalidData("parse REPORT SUPPORTED TASK MANAGEMENT FUNCTIONS response"))
+	}
+
+	/// Returns command timeouts that device recommends, in a form suitable for [`SCSIDevice::set_command_timeouts`](../struct.SCSIDevice.html#method.set_command_timeouts).
+	fn recommended_timeouts(&self) -> Result<HashMap<(u8, Option<u16>), u32>, Error> {
+		Ok(self.supported_commands()?.into_iter()
+			.filter_map(|cmd| match cmd.timeouts {
+				Some(timeouts) if timeouts.recommended != 0 =>
+					Some(((cmd.opcode, cmd.service_action), timeouts.recommended)),
+				_ => None,
+			})
+			.collect()
+		)
+	}
+}
+
+impl Commands for SCSIDevice {}
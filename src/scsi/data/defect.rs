@@ -0,0 +1,143 @@
+/*!
+Functions to parse and structs to represent READ DEFECT DATA responses.
+
+See SBC-3, 5.15 READ DEFECT DATA (10) command, 5.16 READ DEFECT DATA (12) command, and 6.2 Address descriptor formats.
+*/
+
+use byteorder::{ReadBytesExt, BigEndian};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serializable", derive(Serialize))]
+pub enum Format {
+	/// 4-byte LBAs
+	ShortBlock,
+	/// 8-byte LBAs
+	LongBlock,
+	/// Cylinder, head and the number of bytes from index
+	BytesFromIndex,
+	/// Cylinder, head and sector
+	PhysicalSector,
+	VendorSpecific,
+	Reserved(u8),
+}
+
+impl Format {
+	pub fn from(x: u8) -> Self {
+		use self::Format::*;
+		match x {
+			0b000 => ShortBlock,
+			0b011 => LongBlock,
+			0b100 => BytesFromIndex,
+			0b101 => PhysicalSector,
+			0b110 => VendorSpecific,
+			x => Reserved(x),
+		}
+	}
+
+	pub fn as_u8(&self) -> u8 {
+		use self::Format::*;
+		match *self {
+			ShortBlock => 0b000,
+			LongBlock => 0b011,
+			BytesFromIndex => 0b100,
+			PhysicalSector => 0b101,
+			VendorSpecific => 0b110,
+			Reserved(x) => x & 0b111,
+		}
+	}
+
+	/// Size of a single address descriptor, in bytes
+	fn descriptor_len(&self) -> Option<usize> {
+		use self::Format::*;
+		match *self {
+			ShortBlock => Some(4),
+			LongBlock | BytesFromIndex | PhysicalSector => Some(8),
+			VendorSpecific | Reserved(_) => None,
+		}
+	}
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serializable", derive(Serialize))]
+pub enum Defect {
+	Block(u64),
+	BytesFromIndex { cylinder: u32, head: u8, bytes_from_index: u32 },
+	PhysicalSector { cylinder: u32, head: u8, sector: u32 },
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serializable", derive(Serialize))]
+pub struct DefectList {
+	/// Whether the primary defect list (P-list) is included
+	pub plist: bool,
+	/// Whether the grown defect list (G-list) is included
+	pub glist: bool,
+	/// Format of the address descriptors; device might return format other than requested
+	pub format: Format,
+	/// Changes every time the defect list changes; READ DEFECT DATA (12) only
+	pub generation: Option<u16>,
+	/// Number of the defects in the list, as reported in the header; list itself might be truncated, or might be omitted altogether
+	pub count: Option<u32>,
+	pub defects: Vec<Defect>,
+}
+
+fn parse_descriptors(format: Format, data: &[u8]) -> Vec<Defect> {
+	let len = match format.descriptor_len() {
+		Some(len) => len,
+		None => return vec![],
+	};
+
+	data.chunks(len)
+		.filter(|d| d.len() == len)
+		.map(|d| {
+			let cylinder = ((d[0] as u32) << 16) + ((d[1] as u32) << 8) + (d[2] as u32);
+			match format {
+				Format::ShortBlock => Defect::Block((&d[0..4]).read_u32::<BigEndian>().unwrap() as u64),
+				Format::LongBlock => Defect::Block((&d[0..8]).read_u64::<BigEndian>().unwrap()),
+				Format::BytesFromIndex => Defect::BytesFromIndex {
+					cylinder: cylinder,
+					head: d[3],
+					bytes_from_index: (&d[4..8]).read_u32::<BigEndian>().unwrap(),
+				},
+				Format::PhysicalSector => Defect::PhysicalSector {
+					cylinder: cylinder,
+					head: d[3],
+					sector: (&d[4..8]).read_u32::<BigEndian>().unwrap(),
+				},
+				Format::VendorSpecific | Format::Reserved(_) => unreachable!(),
+			}
+		})
+		.collect()
+}
+
+fn parse(data: &[u8], header_len: usize, list_len: usize, generation: Option<u16>) -> DefectList {
+	let format = Format::from(data[1] & 0b111);
+
+	let end = ::std::cmp::min(data.len(), header_len + list_len);
+
+	DefectList {
+		plist: data[1] & 0b1_0000 != 0,
+		glist: data[1] & 0b1000 != 0,
+		format: format,
+		generation: generation,
+		count: format.descriptor_len().map(|len| (list_len / len) as u32),
+		defects: parse_descriptors(format, &data[header_len .. end]),
+	}
+}
+
+/// Parses READ DEFECT DATA (10) response; returns `None` if response is too short to contain the header.
+pub fn parse_10(data: &[u8]) -> Option<DefectList> {
+	if data.len() < 4 { return None; }
+
+	let list_len = (&data[2..4]).read_u16::<BigEndian>().unwrap() as usize;
+	Some(parse(data, 4, list_len, None))
+}
+
+/// Parses READ DEFECT DATA (12) response; returns `None` if response is too short to contain the header.
+pub fn parse_12(data: &[u8]) -> Option<DefectList> {
+	if data.len() < 8 { return None; }
+
+	let generation = (&data[2..4]).read_u16::<BigEndian>().unwrap();
+	let list_len = (&data[4..8]).read_u32::<BigEndian>().unwrap() as usize;
+	Some(parse(data, 8, list_len, Some(generation)))
+}
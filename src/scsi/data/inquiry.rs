@@ -1,16 +1,269 @@
+/*!
+Functions to parse and structs to represent standard INQUIRY data.
+
+See SPC-4, 6.4.2 Standard INQUIRY data.
+*/
+
+use byteorder::{ReadBytesExt, BigEndian};
+
+use super::ascii;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serializable", derive(Serialize))]
+pub enum DeviceType {
+	/// Direct access block device (e.g. magnetic disk), SBC
+	DirectAccess,
+	/// Sequential-access device (e.g. magnetic tape), SSC
+	SequentialAccess,
+	Printer,
+	Processor,
+	/// Write-once device (e.g. some optical disks), SBC
+	WriteOnce,
+	/// CD/DVD device, MMC
+	CDDVD,
+	Scanner,
+	/// Optical memory device (e.g. some optical disks), SBC
+	OpticalMemory,
+	/// Medium changer device (e.g. jukeboxes), SMC
+	MediumChanger,
+	Communications,
+	/// Storage array controller device (e.g. RAID), SCC
+	StorageArrayController,
+	/// Enclosure services device, SES
+	EnclosureServices,
+	/// Simplified direct-access device (e.g. magnetic disk), RBC
+	SimplifiedDirectAccess,
+	/// Optical card reader/writer device, OCRW
+	OpticalCard,
+	/// Bridge controller commands, BCC
+	BridgeController,
+	/// Object-based storage device, OSD
+	ObjectStorage,
+	/// Automation/drive interface, ADC
+	Automation,
+	/// Host managed zoned block device, ZBC
+	HostManagedZoned,
+	WellKnownLogicalUnit,
+	/// Unknown or no device type
+	Unknown,
+	Obsolete(u8),
+	Reserved(u8),
+}
+
+impl DeviceType {
+	pub fn from(x: u8) -> Self {
+		use self::DeviceType::*;
+		match x {
+			0x00 => DirectAccess,
+			0x01 => SequentialAccess,
+			0x02 => Printer,
+			0x03 => Processor,
+			0x04 => WriteOnce,
+			0x05 => CDDVD,
+			0x06 => Scanner,
+			0x07 => OpticalMemory,
+			0x08 => MediumChanger,
+			0x09 => Communications,
+			x @ 0x0a ... 0x0b => Obsolete(x),
+			0x0c => StorageArrayController,
+			0x0d => EnclosureServices,
+			0x0e => SimplifiedDirectAccess,
+			0x0f => OpticalCard,
+			0x10 => BridgeController,
+			0x11 => ObjectStorage,
+			0x12 => Automation,
+			0x14 => HostManagedZoned,
+			0x1e => WellKnownLogicalUnit,
+			0x1f => Unknown,
+			x => Reserved(x),
+		}
+	}
+}
+
+/// Version of SPC standard that device claims to conform to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serializable", derive(Serialize))]
+pub enum Version {
+	/// Device does not claim conformance to any standard
+	NoStandard,
+	/// SCSI-1 or SCSI-2
+	Obsolete(u8),
+	SPC,
+	SPC2,
+	SPC3,
+	SPC4,
+	SPC5,
+	Reserved(u8),
+}
+
+/// Target Port Group Support, i.e. asymmetric logical unit access
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serializable", derive(Serialize))]
+pub enum TPGS {
+	NotSupported,
+	/// Only implicit asymmetric logical unit access is supported
+	Implicit,
+	/// Only explicit asymmetric logical unit access is supported (via SET TARGET PORT GROUPS)
+	Explicit,
+	Both,
+}
+
+/// Task management model that device supports; see SAM-4
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serializable", derive(Serialize))]
+pub enum TaskManagement {
+	/// Device does not support command queuing (obsolete)
+	NoQueuing,
+	/// Basic task management model (obsolete)
+	Basic,
+	Full,
+	/// BQue and CmdQue bits are both set
+	Invalid,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serializable", derive(Serialize))]
+pub struct VersionDescriptor {
+	pub code: u16,
+	/// Name of the standard this descriptor claims conformance to (e.g. `SAS-2`), or `None` if it is not known
+	pub standard: Option<&'static str>,
+}
+
+/// Version descriptors are grouped by 32 codes per standard (e.g. 0C20h–0C3Fh for various revisions of SAS-2); `(first code of the group, standard)`
+static STANDARDS: &[(u16, &str)] = &[
+	(0x0020, "SAM"),
+	(0x0040, "SAM-2"),
+	(0x0060, "SAM-3"),
+	(0x0080, "SAM-4"),
+	(0x00a0, "SAM-5"),
+	(0x00c0, "SAM-6"),
+	(0x0120, "SPC"),
+	(0x0140, "MMC"),
+	(0x0160, "SCC"),
+	(0x0180, "SBC"),
+	(0x01a0, "SMC"),
+	(0x01c0, "SES"),
+	(0x01e0, "SCC-2"),
+	(0x0200, "SSC"),
+	(0x0220, "RBC"),
+	(0x0240, "MMC-2"),
+	(0x0260, "SPC-2"),
+	(0x0280, "OCRW"),
+	(0x02a0, "MMC-3"),
+	(0x02c0, "RMC"),
+	(0x02e0, "SMC-2"),
+	(0x0300, "SPC-3"),
+	(0x0320, "SBC-2"),
+	(0x0340, "OSD"),
+	(0x0360, "SSC-2"),
+	(0x0380, "BCC"),
+	(0x03a0, "MMC-4"),
+	(0x03c0, "ADC"),
+	(0x03e0, "SES-2"),
+	(0x0400, "SSC-3"),
+	(0x0420, "MMC-5"),
+	(0x0440, "OSD-2"),
+	(0x0460, "SPC-4"),
+	(0x0480, "SMC-3"),
+	(0x04a0, "ADC-2"),
+	(0x04c0, "SBC-3"),
+	(0x04e0, "MMC-6"),
+	(0x0500, "ADC-3"),
+	(0x0520, "SSC-4"),
+	(0x0560, "OSD-3"),
+	(0x0580, "SES-3"),
+	(0x05a0, "SSC-5"),
+	(0x05c0, "SPC-5"),
+	(0x05e0, "SFSC"),
+	(0x0600, "SBC-4"),
+	(0x0620, "ZBC"),
+	(0x0820, "SSA-TL2"),
+	(0x0840, "SSA-TL1"),
+	(0x0860, "SSA-S3P"),
+	(0x0880, "SSA-S2P"),
+	(0x08a0, "SIP"),
+	(0x08c0, "FCP"),
+	(0x08e0, "SBP-2"),
+	(0x0900, "FCP-2"),
+	(0x0920, "SST"),
+	(0x0940, "SRP"),
+	(0x0960, "iSCSI"),
+	(0x0980, "SBP-3"),
+	(0x09a0, "SRP-2"),
+	(0x09c0, "ADP"),
+	(0x09e0, "ADT"),
+	(0x0a00, "FCP-3"),
+	(0x0a20, "ADT-2"),
+	(0x0a40, "FCP-4"),
+	(0x0a60, "ADT-3"),
+	(0x0aa0, "SPI"),
+	(0x0ac0, "Fast-20"),
+	(0x0ae0, "SPI-2"),
+	(0x0b00, "SPI-3"),
+	(0x0b20, "EPI"),
+	(0x0b40, "SPI-4"),
+	(0x0b60, "SPI-5"),
+	(0x0be0, "SAS"),
+	(0x0c00, "SAS-1.1"),
+	(0x0c20, "SAS-2"),
+	(0x0c40, "SAS-2.1"),
+	(0x0c60, "SAS-3"),
+	(0x0c80, "SAS-4"),
+	(0x0d20, "FC-PH"),
+	(0x0d40, "FC-AL"),
+	(0x0d60, "FC-AL-2"),
+	(0x0d80, "FC-PH-3"),
+	(0x0da0, "FC-FS"),
+	(0x0dc0, "FC-PI"),
+	(0x0de0, "FC-PI-2"),
+	(0x0e00, "FC-FS-2"),
+	(0x0e20, "FC-LS"),
+	(0x0e40, "FC-SP"),
+	(0x0e60, "FC-PI-3"),
+	(0x0e80, "FC-PI-4"),
+	(0x15e0, "ATA/ATAPI-6"),
+	(0x1600, "ATA/ATAPI-7"),
+	(0x1620, "ATA/ATAPI-8"),
+	(0x1740, "UAS"),
+	(0x1ea0, "SAT"),
+	(0x1ec0, "SAT-2"),
+	(0x1ee0, "SAT-3"),
+	(0x1f00, "SAT-4"),
+	(0x20a0, "SPL"),
+	(0x20c0, "SPL-2"),
+	(0x20e0, "SPL-3"),
+	(0x2100, "SPL-4"),
+];
+
+impl VersionDescriptor {
+	pub fn from(code: u16) -> Self {
+		VersionDescriptor {
+			code: code,
+			standard: STANDARDS.iter()
+				.find(|&&(group, _)| group == code & !0x1f)
+				.map(|&(_, name)| name),
+		}
+	}
+}
+
 #[derive(Debug)]
 #[cfg_attr(feature = "serializable", derive(Serialize))]
 pub struct Inquiry {
 	pub connected: Option<bool>,
-	pub device_type: String, // TODO enum?
+	pub device_type: DeviceType,
 
 	pub removable: bool,
 
+	pub version: Version,
+	/// Response data format; should always be 2
+	pub response_data_format: u8,
+
 	pub naca_bit: bool, /// Normal ACA bit support, see SAM-3
 	pub hier_addressing: bool,
 
 	pub scc:  bool,
 	pub acc:  bool,
+	pub tpgs: TPGS,
 	pub tpc:  bool,
 	pub protection:  bool,
 
@@ -18,11 +271,14 @@ pub struct Inquiry {
 	pub multiport: bool,
 	pub media_changer: bool,
 	pub linked_cmds: bool,
+	pub task_management: TaskManagement,
 
 	pub vendor_id: String,
 	pub product_id: String,
 	pub product_rev: String,
-	pub drive_serial: String,
+
+	/// Standards that device claims conformance to, e.g. SAM, SPC, SBC and transport protocols
+	pub version_descriptors: Vec<VersionDescriptor>,
 }
 
 fn is_set(x: u8, bit: usize) -> bool {
@@ -39,48 +295,39 @@ pub fn parse_inquiry(data: &Vec<u8>) -> Inquiry {
 			// 100..111 is vendor specific
 			_ => None,
 		},
-		device_type: match data[0] & 0b0001_1111 {
-			0x00 => "SBC-2", // Direct access block device (e.g., magnetic disk)
-			0x01 => "SSC-2", // Sequential-access device (e.g., magnetic tape)
-			0x02 => "SSC", // Printer device
-			0x03 => "SPC-2", // Processor device
-			0x04 => "SBC", // Write-once device (e.g., some optical disks)
-			0x05 => "MMC-4", // CD/DVD device
-			0x06 => "Scanner device", // (obsolete)
-			0x07 => "SBC", // Optical memory device (e.g., some optical disks)
-			0x08 => "SMC-2", // Medium changer device (e.g., jukeboxes)
-			0x09 => "Communications device", // (obsolete)
-			0x0A | 0x0B => "?", // Obsolete
-			0x0C => "SCC-2", // Storage array controller device (e.g., RAID)
-			0x0D => "SES", // Enclosure services device
-			0x0E => "RBC", // Simplified direct-access device (e.g., magnetic disk)
-			0x0F => "OCRW", // Optical card reader/writer device
-			0x10 => "BCC", // Bridge Controller Commands
-			0x11 => "OSD", // Object-based Storage Device
-			0x12 => "ADC", // Automation/Drive Interface
-			0x13 | 0x1D => "Reserved",
-			0x1E => "Well known logical unit",
-			0x1F => "Unknown or no device type",
-			_ => unreachable!(),
-		}.to_string(),
+		device_type: DeviceType::from(data[0] & 0b0001_1111),
 
 		removable: is_set(data[1], 7),
 
-		/* TODO data[2] → Version:
-		0x03 SPC
-		0x04 SPC-2
-		the rest is ???
-		*/
+		version: {
+			use self::Version::*;
+			match data[2] {
+				0x00 => NoStandard,
+				x @ 0x01 ... 0x02 => Obsolete(x),
+				0x03 => SPC,
+				0x04 => SPC2,
+				0x05 => SPC3,
+				0x06 => SPC4,
+				0x07 => SPC5,
+				x => Reserved(x),
+			}
+		},
+		response_data_format: data[3] & 0b1111,
 
 		naca_bit: is_set(data[3], 5),
 		hier_addressing: is_set(data[3], 4),
 
-		// ResponseFormat: data[3] & 0b1111, // TODO
 		// data[4]: additional length
 
 		scc: is_set(data[5], 7), // storage array controller component support
 		acc: is_set(data[5], 6), // device contains an access controls coordinator
-		// TODO? (data[5] & 0b0011_0000) Target Port Group Support
+		tpgs: match (data[5] & 0b0011_0000) >> 4 {
+			0b00 => TPGS::NotSupported,
+			0b01 => TPGS::Implicit,
+			0b10 => TPGS::Explicit,
+			0b11 => TPGS::Both,
+			_ => unreachable!(),
+		},
 		tpc: is_set(data[5], 3), // support for 3rd-party copy commands
 		protection: is_set(data[5], 0),
 
@@ -89,19 +336,29 @@ pub fn parse_inquiry(data: &Vec<u8>) -> Inquiry {
 		media_changer: is_set(data[6], 3),
 		linked_cmds: is_set(data[7], 3),
 
-		/* TODO? match (bque: is_set(data[6], 7), cmdque: is_set(data[7], 1)):
-		00 obsolete
-		01 full task mgmt model
-		01 basic task mgmt model
-		11 invalid
-		*/
+		task_management: match (is_set(data[6], 7), is_set(data[7], 1)) { // (bque, cmdque)
+			(false, false) => TaskManagement::NoQueuing,
+			(false, true) => TaskManagement::Full,
+			(true, false) => TaskManagement::Basic,
+			(true, true) => TaskManagement::Invalid,
+		},
 
-		// XXX? > ASCII data fields … may be terminated with one or more ASCII null (00h) characters.
-		vendor_id: String::from_utf8(data[8..16].to_vec()).unwrap().trim().to_string(),
-		product_id: String::from_utf8(data[16..32].to_vec()).unwrap().trim().to_string(),
-		product_rev: String::from_utf8(data[32..36].to_vec()).unwrap().trim().to_string(),
-		drive_serial: String::from_utf8(data[36..44].to_vec()).unwrap().trim().to_string(),
+		vendor_id: ascii(&data[8..16]),
+		product_id: ascii(&data[16..32]),
+		product_rev: ascii(&data[32..36]),
+		// data[36..56] is vendor-specific; use Unit Serial Number VPD page (80h) to get the serial number
 
-		// TODO TODO TODO TODO TODO
+		version_descriptors: {
+			// data[4] is additional length, i.e. the length of the data starting from data[5]
+			let len = ::std::cmp::min(data.len(), data[4] as usize + 5);
+			if len > 58 {
+				data[58 .. ::std::cmp::min(len, 74)].chunks(2)
+					.filter(|x| x.len() == 2)
+					.map(|x| (&x[..]).read_u16::<BigEndian>().unwrap())
+					.filter(|&x| x != 0)
+					.map(VersionDescriptor::from)
+					.collect()
+			} else { vec![] }
+		},
 	}
 }
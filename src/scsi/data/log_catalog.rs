@@ -0,0 +1,154 @@
+/*!
+Names of the standard log pages and their parameters.
+
+See SPC-4, 7.3 Log parameters, and SBC-3, 6.4 Log parameters.
+
+## Example
+
+```
+use hdd::scsi::data::log_catalog;
+
+assert_eq!(log_catalog::page_name(0x0d, 0x01), Some("Environmental Reporting"));
+assert_eq!(log_catalog::param_name(0x0d, 0x00, 0x0001), Some("Reference temperature"));
+```
+*/
+
+/// `(page, subpage, name)`
+static PAGES: &[(u8, u8, &str)] = &[
+	(0x00, 0x00, "Supported Log Pages"),
+	(0x00, 0xff, "Supported Log Pages and Subpages"),
+	(0x02, 0x00, "Write Error Counter"),
+	(0x03, 0x00, "Read Error Counter"),
+	(0x04, 0x00, "Read Reverse Error Counter"),
+	(0x05, 0x00, "Verify Error Counter"),
+	(0x06, 0x00, "Non-Medium Error"),
+	(0x07, 0x00, "Last n Error Events"),
+	(0x08, 0x00, "Format Status"),
+	(0x0b, 0x00, "Last n Deferred Errors or Asynchronous Events"),
+	(0x0c, 0x00, "Logical Block Provisioning"),
+	(0x0d, 0x00, "Temperature"),
+	(0x0d, 0x01, "Environmental Reporting"),
+	(0x0d, 0x02, "Environmental Limits"),
+	(0x0e, 0x00, "Start-Stop Cycle Counter"),
+	(0x0e, 0x01, "Utilization"),
+	(0x0f, 0x00, "Application Client"),
+	(0x10, 0x00, "Self-Test Results"),
+	(0x11, 0x00, "Solid State Media"),
+	(0x15, 0x00, "Background Scan Results"),
+	(0x15, 0x01, "Pending Defects"),
+	(0x15, 0x02, "Background Operation"),
+	(0x15, 0x03, "LPS Misalignment"),
+	(0x16, 0x00, "ATA PASS-THROUGH Results"),
+	(0x17, 0x00, "Non-Volatile Cache"),
+	(0x18, 0x00, "Protocol-Specific Port"),
+	(0x19, 0x00, "General Statistics and Performance"),
+	(0x19, 0x20, "Cache Memory Statistics"),
+	(0x1a, 0x00, "Power Condition Transitions"),
+	(0x2f, 0x00, "Informational Exceptions"),
+];
+
+/// `(page, subpage, first param code, last param code, name)`
+static PARAMS: &[(u8, u8, u16, u16, &str)] = &[
+	// error counter pages are handled separately, see `error_counter_name`
+	(0x06, 0x00, 0x0000, 0x0000, "Non-medium error count"),
+
+	(0x07, 0x00, 0x0000, 0x003f, "Error event"),
+
+	(0x08, 0x00, 0x0000, 0x0000, "Format data out"),
+	(0x08, 0x00, 0x0001, 0x0001, "Grown defects during certification"),
+	(0x08, 0x00, 0x0002, 0x0002, "Total blocks reassigned during format"),
+	(0x08, 0x00, 0x0003, 0x0003, "Total new blocks reassigned"),
+	(0x08, 0x00, 0x0004, 0x0004, "Power on minutes since format"),
+
+	(0x0b, 0x00, 0x0000, 0x003f, "Deferred error or asynchronous event"),
+
+	(0x0c, 0x00, 0x0001, 0x0001, "Available LBA mapping resource count"),
+	(0x0c, 0x00, 0x0002, 0x0002, "Used LBA mapping resource count"),
+	(0x0c, 0x00, 0x0003, 0x0003, "Available provisioning resource percentage"),
+	(0x0c, 0x00, 0x0100, 0x0100, "De-duplicated LBA resource count"),
+	(0x0c, 0x00, 0x0101, 0x0101, "Compressed LBA resource count"),
+	(0x0c, 0x00, 0x0102, 0x0102, "Total efficiency LBA resource count"),
+
+	(0x0d, 0x00, 0x0000, 0x0000, "Temperature"),
+	(0x0d, 0x00, 0x0001, 0x0001, "Reference temperature"),
+	(0x0d, 0x01, 0x0000, 0x00ff, "Temperature report"),
+	(0x0d, 0x01, 0x0100, 0x01ff, "Relative humidity report"),
+	(0x0d, 0x02, 0x0000, 0x00ff, "Temperature limits"),
+	(0x0d, 0x02, 0x0100, 0x01ff, "Relative humidity limits"),
+
+	(0x0e, 0x00, 0x0001, 0x0001, "Date of manufacture"),
+	(0x0e, 0x00, 0x0002, 0x0002, "Accounting date"),
+	(0x0e, 0x00, 0x0003, 0x0003, "Specified cycle count over device lifetime"),
+	(0x0e, 0x00, 0x0004, 0x0004, "Accumulated start-stop cycles"),
+	(0x0e, 0x00, 0x0005, 0x0005, "Specified load-unload count over device lifetime"),
+	(0x0e, 0x00, 0x0006, 0x0006, "Accumulated load-unload cycles"),
+	(0x0e, 0x01, 0x0000, 0x0000, "Workload utilization"),
+	(0x0e, 0x01, 0x0001, 0x0001, "Utilization usage rate based on date and time"),
+
+	(0x0f, 0x00, 0x0000, 0x0fff, "General usage application client data"),
+
+	(0x10, 0x00, 0x0001, 0x0014, "Self-test result"),
+
+	(0x11, 0x00, 0x0001, 0x0001, "Percentage used endurance indicator"),
+
+	(0x15, 0x00, 0x0000, 0x0000, "Background scan status"),
+	(0x15, 0x00, 0x0001, 0x0800, "Background scan result"),
+	(0x15, 0x01, 0x0000, 0x0000, "Pending defect count"),
+	(0x15, 0x01, 0x0001, 0xf000, "Pending defect"),
+	(0x15, 0x02, 0x0000, 0x0000, "Background operation"),
+
+	(0x16, 0x00, 0x0000, 0x000e, "ATA PASS-THROUGH result"),
+
+	(0x17, 0x00, 0x0000, 0x0000, "Remaining non-volatile time"),
+	(0x17, 0x00, 0x0001, 0x0001, "Maximum non-volatile time"),
+
+	(0x18, 0x00, 0x0000, 0xffff, "Port"),
+
+	(0x19, 0x00, 0x0001, 0x0001, "General access statistics and performance"),
+	(0x19, 0x00, 0x0002, 0x0002, "Idle time"),
+	(0x19, 0x00, 0x0003, 0x0003, "Time interval"),
+	(0x19, 0x00, 0x0004, 0x0004, "Force unit access statistics and performance"),
+
+	(0x1a, 0x00, 0x0001, 0x0001, "Accumulated transitions to active"),
+	(0x1a, 0x00, 0x0002, 0x0002, "Accumulated transitions to idle_a"),
+	(0x1a, 0x00, 0x0003, 0x0003, "Accumulated transitions to idle_b"),
+	(0x1a, 0x00, 0x0004, 0x0004, "Accumulated transitions to idle_c"),
+	(0x1a, 0x00, 0x0008, 0x0008, "Accumulated transitions to standby_z"),
+	(0x1a, 0x00, 0x0009, 0x0009, "Accumulated transitions to standby_y"),
+
+	(0x2f, 0x00, 0x0000, 0x0000, "Informational exception"),
+];
+
+/// Parameters of the Write, Read, Read Reverse and Verify Error Counter pages (02h–05h)
+fn error_counter_name(code: u16) -> Option<&'static str> {
+	match code {
+		0x0000 => Some("Errors corrected without substantial delay"),
+		0x0001 => Some("Errors corrected with possible delays"),
+		0x0002 => Some("Total rewrites or rereads"),
+		0x0003 => Some("Total errors corrected"),
+		0x0004 => Some("Total times correction algorithm processed"),
+		0x0005 => Some("Total bytes processed"),
+		0x0006 => Some("Total uncorrected errors"),
+		_ => None,
+	}
+}
+
+/// For a given log page `page`, subpage `subpage`, returns its name, or `None` if this page is not in the catalog.
+pub fn page_name(page: u8, subpage: u8) -> Option<&'static str> {
+	PAGES.iter()
+		.find(|&&(p, sp, _)| p == page && sp == subpage)
+		.map(|&(_, _, name)| name)
+}
+
+/// For a given parameter `code` of the log page `page`, subpage `subpage`, returns its name, or `None` if this parameter is not in the catalog.
+pub fn param_name(page: u8, subpage: u8, code: u16) -> Option<&'static str> {
+	if subpage == 0x00 {
+		if let 0x02 ... 0x05 = page {
+			return error_counter_name(code);
+		}
+	}
+
+	PARAMS.iter()
+		.find(|&&(p, sp, first, last, _)| p == page && sp == subpage && code >= first && code <= last)
+		.map(|&(_, _, _, _, name)| name)
+}
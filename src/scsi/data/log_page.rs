@@ -22,6 +22,9 @@ for param in params {
 
 use byteorder::{ReadBytesExt, BigEndian};
 
+use scsi::data::ascii;
+use scsi::data::log_catalog;
+
 /**
 When devices server should establish a unit attention condition (SAM-4).
 
@@ -156,3 +159,117 @@ pub fn parse(data: &[u8]) -> Option<Page> {
 		data: data[4 .. len].to_vec(),
 	})
 }
+
+/// Composes log page `page`, subpage `subpage` (`None` for pages without subpages) with parameters `params`, e.g. for LOG SELECT.
+pub fn compose(page: u8, subpage: Option<u8>, params: &[Parameter]) -> Vec<u8> {
+	let data: Vec<u8> = params.iter().flat_map(|p| p.to_bytes()).collect();
+
+	let mut out = vec![
+		// DS bit is not set: saving parameters is not disabled
+		(page & 0b11_1111) + if subpage.is_some() { 0b100_0000 } else { 0 },
+		subpage.unwrap_or(0),
+		// TODO Err() if data.len() > 0xffff
+		(data.len() >> 8) as u8,
+		(data.len() & 0xff) as u8,
+	];
+	out.extend(data);
+
+	out
+}
+
+/// Value of the log parameter, interpreted according to its format
+#[derive(Debug, Clone)]
+pub enum Value {
+	Counter(u64),
+	/// ASCII list, with surrounding whitespace and NULs trimmed
+	ASCII(String),
+	/// Binary list, or a counter that does not fit into `u64`
+	Binary(Vec<u8>),
+}
+
+/// Log parameter interpreted without any knowledge of the page it came from, other than the names from [the catalog](../log_catalog/index.html)
+#[derive(Debug)]
+pub struct DecodedParameter {
+	pub code: u16,
+	pub name: Option<&'static str>,
+	pub threshold_comparison: Condition,
+	pub value: Value,
+}
+
+impl Parameter {
+	/// Interprets parameter value according to its format bits.
+	pub fn decode_value(&self) -> Value {
+		match self.format {
+			Format::BoundedCounter | Format::UnboundedCounter => {
+				// skip leading zeroes to see whether the value fits into u64
+				let offset = self.value.iter().position(|&x| x != 0).unwrap_or(self.value.len());
+				let len = self.value.len() - offset;
+
+				if self.value.is_empty() || len > 8 {
+					Value::Binary(self.value.clone())
+				} else if len == 0 {
+					Value::Counter(0)
+				} else {
+					Value::Counter((&self.value[offset..]).read_uint::<BigEndian>(len).unwrap())
+				}
+			},
+			Format::ASCIIList => Value::ASCII(ascii(&self.value)),
+			Format::BinaryList => Value::Binary(self.value.clone()),
+		}
+	}
+
+	/// Composes parameter header and value into a buffer, e.g. for LOG SELECT.
+	pub fn to_bytes(&self) -> Vec<u8> {
+		let mut out = vec![];
+
+		out.push((self.code >> 8) as u8);
+		out.push((self.code & 0xff) as u8);
+		out.push(
+			if self.update_disabled { 0b1000_0000 } else { 0 }
+			+ if self.target_save { 0b10_0000 } else { 0 }
+			+ {
+				use self::Condition::*;
+				match self.threshold_comparison {
+					Never => 0,
+					Always => 0b1_0000,
+					Eq => 0b1_0100,
+					Ne => 0b1_1000,
+					Gt => 0b1_1100,
+				}
+			}
+			+ match self.format {
+				Format::BoundedCounter => 0b00,
+				Format::ASCIIList => 0b01,
+				Format::UnboundedCounter => 0b10,
+				Format::BinaryList => 0b11,
+			}
+		);
+		// TODO Err() if value.len() > 0xff
+		out.push(self.value.len() as u8);
+		out.extend_from_slice(&self.value);
+
+		out
+	}
+}
+
+impl Page {
+	/**
+	Parses page data as list of params, and interprets each of these using param format bits and the catalog of known page parameters.
+
+	This works for any page that contains params, including the ones that this crate knows nothing about.
+	*/
+	pub fn decode(&self) -> Option<Vec<DecodedParameter>> {
+		let (page, subpage) = (self.page, self.subpage.unwrap_or(0));
+		self.decode_with(|code| log_catalog::param_name(page, subpage, code))
+	}
+
+	/// Same as [decode](#method.decode), but takes param names from `param_name` instead of the catalog; use this to decode vendor-specific pages.
+	pub fn decode_with<F: Fn(u16) -> Option<&'static str>>(&self, param_name: F) -> Option<Vec<DecodedParameter>> {
+		Some(self.parse_params()?.iter().map(|param| DecodedParameter {
+			code: param.code,
+			name: param_name(param.code),
+			threshold_comparison: param.threshold_comparison,
+			value: param.decode_value(),
+		}).collect())
+	}
+}
@@ -0,0 +1,75 @@
+/*!
+Functions to parse and structs to represent REPORT LUNS response.
+
+See SPC-4, 6.33 REPORT LUNS command, and SAM-5, 4.7 Logical unit numbers.
+*/
+
+use byteorder::{ReadBytesExt, BigEndian};
+
+/// Address of the logical unit at the first level of the LUN hierarchy
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serializable", derive(Serialize))]
+pub enum Address {
+	/// Peripheral device addressing method; `bus` 0 means `lun` is the logical unit in the current target
+	Peripheral { bus: u8, lun: u8 },
+	/// Flat space addressing method
+	Flat(u16),
+	/// Logical unit addressing method
+	LogicalUnit { target: u8, bus: u8, lun: u8 },
+	/// Extended logical unit addressing method (e.g. well known logical units); see the raw LUN
+	Extended,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serializable", derive(Serialize))]
+pub struct LUN {
+	/// LUN as reported by the device, all 8 bytes
+	pub raw: u64,
+	pub address: Address,
+	/// Whether LUN has more than one level, i.e. is a logical unit behind another logical unit
+	pub hierarchical: bool,
+}
+
+impl LUN {
+	pub fn from(raw: u64) -> Self {
+		let b0 = (raw >> 56) as u8;
+		let b1 = (raw >> 48) as u8;
+
+		LUN {
+			raw: raw,
+			address: match b0 >> 6 {
+				0b00 => Address::Peripheral { bus: b0 & 0b0011_1111, lun: b1 },
+				0b01 => Address::Flat((((b0 & 0b0011_1111) as u16) << 8) + b1 as u16),
+				0b10 => Address::LogicalUnit { target: b0 & 0b0011_1111, bus: b1 >> 5, lun: b1 & 0b1_1111 },
+				0b11 => Address::Extended,
+				_ => unreachable!(),
+			},
+			// peripheral device addressing with non-zero bus refers to the next level, as does the non-zero remainder
+			hierarchical: raw & 0x0000_ffff_ffff_ffff != 0,
+		}
+	}
+
+	/// Returns single-level logical unit number (as used in e.g. `H:C:T:L` on Linux), or `None` for hierarchical or extended LUNs.
+	pub fn number(&self) -> Option<u16> {
+		if self.hierarchical { return None; }
+		match self.address {
+			Address::Peripheral { bus: 0, lun } => Some(lun as u16),
+			Address::Flat(lun) => Some(lun),
+			_ => None,
+		}
+	}
+}
+
+/// Parses REPORT LUNS response; returns `None` if response is too short to contain the header.
+pub fn parse(data: &[u8]) -> Option<Vec<LUN>> {
+	if data.len() < 8 { return None; }
+
+	let len = (&data[0..4]).read_u32::<BigEndian>().unwrap() as usize;
+	let end = ::std::cmp::min(data.len(), 8 + len);
+
+	Some(data[8 .. end].chunks(8)
+		.filter(|x| x.len() == 8)
+		.map(|x| LUN::from((&x[..]).read_u64::<BigEndian>().unwrap()))
+		.collect()
+	)
+}
@@ -2,3 +2,16 @@ pub mod inquiry;
 pub mod vpd;
 pub mod sense;
 pub mod log_page;
+pub mod log_catalog;
+pub mod vendor_log_page;
+pub mod mode_page;
+pub mod defect;
+pub mod opcodes;
+pub mod luns;
+pub mod ses;
+pub mod persistent_reserve;
+
+/// Decodes ASCII data field, which might be padded with spaces as well as terminated with one or more null (00h) characters
+pub(crate) fn ascii(data: &[u8]) -> String {
+	String::from_utf8_lossy(data).trim_matches(|c: char| c.is_whitespace() || c == '\0').to_string()
+}
@@ -0,0 +1,101 @@
+/*!
+Caching mode page (08h).
+
+See SBC-3, 6.4.5 Caching mode page.
+*/
+
+use byteorder::{ByteOrder, ReadBytesExt, BigEndian};
+
+use super::{ModePage, is_set, set_bit};
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serializable", derive(Serialize))]
+pub struct Caching {
+	/// Initiator Control: whether cache segmentation is controlled by `cache_segments`/`cache_segment_size` rather than by the device itself
+	pub ic: bool,
+	/// Abort Pre-Fetch
+	pub abpf: bool,
+	/// Caching Analysis Permitted
+	pub cap: bool,
+	/// Discontinuity: whether pre-fetches may cross time discontinuities (e.g. track boundaries)
+	pub disc: bool,
+	/// Whether `cache_segment_size` should be used to control caching segmentation
+	pub size: bool,
+	/// Write Cache Enable
+	pub wce: bool,
+	/// Multiplication Factor: whether pre-fetch values are multiplied by the number of logical blocks in the command
+	pub mf: bool,
+	/// Read Cache Disable
+	pub rcd: bool,
+	pub demand_read_retention_priority: u8,
+	pub write_retention_priority: u8,
+	pub disable_prefetch_transfer_length: u16,
+	pub min_prefetch: u16,
+	pub max_prefetch: u16,
+	pub max_prefetch_ceiling: u16,
+	/// Force Sequential Write
+	pub fsw: bool,
+	/// Logical Block Cache Segment Size: whether `cache_segment_size` is in logical blocks rather than bytes
+	pub lbcss: bool,
+	/// Disable Read-Ahead
+	pub dra: bool,
+	/// Non-Volatile cache Disabled
+	pub nv_dis: bool,
+	pub cache_segments: u8,
+	pub cache_segment_size: u16,
+}
+
+impl ModePage for Caching {
+	fn code() -> (u8, u8) { (0x08, 0x00) }
+
+	fn parse(data: &[u8]) -> Option<Self> {
+		if data.len() < 14 { return None; }
+
+		Some(Caching {
+			ic: is_set(data[0], 7),
+			abpf: is_set(data[0], 6),
+			cap: is_set(data[0], 5),
+			disc: is_set(data[0], 4),
+			size: is_set(data[0], 3),
+			wce: is_set(data[0], 2),
+			mf: is_set(data[0], 1),
+			rcd: is_set(data[0], 0),
+			demand_read_retention_priority: data[1] >> 4,
+			write_retention_priority: data[1] & 0b1111,
+			disable_prefetch_transfer_length: (&data[2..4]).read_u16::<BigEndian>().unwrap(),
+			min_prefetch: (&data[4..6]).read_u16::<BigEndian>().unwrap(),
+			max_prefetch: (&data[6..8]).read_u16::<BigEndian>().unwrap(),
+			max_prefetch_ceiling: (&data[8..10]).read_u16::<BigEndian>().unwrap(),
+			fsw: is_set(data[10], 7),
+			lbcss: is_set(data[10], 6),
+			dra: is_set(data[10], 5),
+			// data[10] bits 4..3 are vendor-specific, 2..1 are SYNC_PROG (SBC-4)
+			nv_dis: is_set(data[10], 0),
+			cache_segments: data[11],
+			cache_segment_size: (&data[12..14]).read_u16::<BigEndian>().unwrap(),
+			// data[14] is reserved, data[15..18] are obsolete
+		})
+	}
+
+	fn write(&self, data: &mut [u8]) {
+		set_bit(&mut data[0], 7, self.ic);
+		set_bit(&mut data[0], 6, self.abpf);
+		set_bit(&mut data[0], 5, self.cap);
+		set_bit(&mut data[0], 4, self.disc);
+		set_bit(&mut data[0], 3, self.size);
+		set_bit(&mut data[0], 2, self.wce);
+		set_bit(&mut data[0], 1, self.mf);
+		set_bit(&mut data[0], 0, self.rcd);
+		data[1] = (self.demand_read_retention_priority << 4) + (self.write_retention_priority & 0b1111);
+		BigEndian::write_u16(&mut data[2..4], self.disable_prefetch_transfer_length);
+		BigEndian::write_u16(&mut data[4..6], self.min_prefetch);
+		BigEndian::write_u16(&mut data[6..8], self.max_prefetch);
+		BigEndian::write_u16(&mut data[8..10], self.max_prefetch_ceiling);
+		set_bit(&mut data[10], 7, self.fsw);
+		set_bit(&mut data[10], 6, self.lbcss);
+		set_bit(&mut data[10], 5, self.dra);
+		set_bit(&mut data[10], 0, self.nv_dis);
+		data[11] = self.cache_segments;
+		BigEndian::write_u16(&mut data[12..14], self.cache_segment_size);
+	}
+}
@@ -0,0 +1,103 @@
+/*!
+Control mode page (0Ah).
+
+See SPC-4, 7.5.8 Control mode page.
+*/
+
+use byteorder::{ByteOrder, ReadBytesExt, BigEndian};
+
+use super::{ModePage, is_set, set_bit};
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serializable", derive(Serialize))]
+pub struct Control {
+	/// Task Set Type: 000b for one task set for all I_T nexuses, 001b for separate task sets for each I_T nexus
+	pub tst: u8,
+	/// Task Management Functions Only
+	pub tmf_only: bool,
+	/// Disable Protection Information Check if protect field is Zero
+	pub dpicz: bool,
+	/// Descriptor format sense data is returned if set, fixed format if not
+	pub d_sense: bool,
+	/// Global Logging Target Save Disable: whether implicit saving of log parameters is disabled
+	pub gltsd: bool,
+	/// Report Log Exception Condition: report log exceptions as recovered errors
+	pub rlec: bool,
+	pub queue_algorithm_modifier: u8,
+	/// No Unit Attention on Release
+	pub nuar: bool,
+	/// Queue Error Management
+	pub qerr: u8,
+	/// Report A Check
+	pub rac: bool,
+	/// Unit attention interlocks control
+	pub ua_intlck_ctrl: u8,
+	/// Software Write Protect
+	pub swp: bool,
+	/// Application Tag Owner
+	pub ato: bool,
+	/// Task Aborted Status
+	pub tas: bool,
+	/// Application Tag Mode Page Enabled
+	pub atmpe: bool,
+	/// Reject Write Without Protection
+	pub rwwp: bool,
+	pub autoload_mode: u8,
+	/// In 100 ms units; ffffh means unlimited
+	pub busy_timeout_period: u16,
+	/// Recommended time to complete an extended self-test, in seconds; ffffh means 65535 seconds or more
+	pub extended_self_test_completion_time: u16,
+}
+
+impl ModePage for Control {
+	fn code() -> (u8, u8) { (0x0a, 0x00) }
+
+	fn parse(data: &[u8]) -> Option<Self> {
+		if data.len() < 10 { return None; }
+
+		Some(Control {
+			tst: data[0] >> 5,
+			tmf_only: is_set(data[0], 4),
+			dpicz: is_set(data[0], 3),
+			d_sense: is_set(data[0], 2),
+			gltsd: is_set(data[0], 1),
+			rlec: is_set(data[0], 0),
+			queue_algorithm_modifier: data[1] >> 4,
+			nuar: is_set(data[1], 3),
+			qerr: (data[1] >> 1) & 0b11,
+			// data[2] bit 7 is vendor-specific
+			rac: is_set(data[2], 6),
+			ua_intlck_ctrl: (data[2] >> 4) & 0b11,
+			swp: is_set(data[2], 3),
+			ato: is_set(data[3], 7),
+			tas: is_set(data[3], 6),
+			atmpe: is_set(data[3], 5),
+			rwwp: is_set(data[3], 4),
+			autoload_mode: data[3] & 0b111,
+			// data[4..6] are obsolete
+			busy_timeout_period: (&data[6..8]).read_u16::<BigEndian>().unwrap(),
+			extended_self_test_completion_time: (&data[8..10]).read_u16::<BigEndian>().unwrap(),
+		})
+	}
+
+	fn write(&self, data: &mut [u8]) {
+		data[0] = (self.tst << 5) + (data[0] & 0b1_1111);
+		set_bit(&mut data[0], 4, self.tmf_only);
+		set_bit(&mut data[0], 3, self.dpicz);
+		set_bit(&mut data[0], 2, self.d_sense);
+		set_bit(&mut data[0], 1, self.gltsd);
+		set_bit(&mut data[0], 0, self.rlec);
+		data[1] = (self.queue_algorithm_modifier << 4) + ((self.qerr & 0b11) << 1) + (data[1] & 0b1);
+		set_bit(&mut data[1], 3, self.nuar);
+		set_bit(&mut data[2], 6, self.rac);
+		data[2] = (data[2] & !0b11_0000) + ((self.ua_intlck_ctrl & 0b11) << 4);
+		set_bit(&mut data[2], 3, self.swp);
+		set_bit(&mut data[3], 7, self.ato);
+		set_bit(&mut data[3], 6, self.tas);
+		set_bit(&mut data[3], 5, self.atmpe);
+		set_bit(&mut data[3], 4, self.rwwp);
+		data[3] = (data[3] & !0b111) + (self.autoload_mode & 0b111);
+		BigEndian::write_u16(&mut data[6..8], self.busy_timeout_period);
+		BigEndian::write_u16(&mut data[8..10], self.extended_self_test_completion_time);
+	}
+}
@@ -0,0 +1,76 @@
+/*!
+Read-Write Error Recovery mode page (01h).
+
+See SBC-3, 6.4.8 Read-Write Error Recovery mode page.
+*/
+
+use byteorder::{ByteOrder, ReadBytesExt, BigEndian};
+
+use super::{ModePage, is_set, set_bit};
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serializable", derive(Serialize))]
+pub struct ErrorRecovery {
+	/// Automatic Write Reallocation Enabled
+	pub awre: bool,
+	/// Automatic Read Reallocation Enabled
+	pub arre: bool,
+	/// Transfer Block: whether to transfer unrecovered block before reporting CHECK CONDITION
+	pub tb: bool,
+	/// Read Continuous: transfer data without delays that error recovery procedures might impose
+	pub rc: bool,
+	/// Enable Early Recovery: use the most expedient form of error recovery at the expense of higher risk of data miscorrection
+	pub eer: bool,
+	/// Post Error: report recovered errors
+	pub per: bool,
+	/// Data Terminate on Error: terminate data transfer upon recovered error (requires PER)
+	pub dte: bool,
+	/// Disable Correction: do not use ECC for error recovery
+	pub dcr: bool,
+	pub read_retry_count: u8,
+	pub write_retry_count: u8,
+	/// Logical Block Provisioning Error Reporting Enabled
+	pub lbpere: bool,
+	/// Maximum time to spend on error recovery, in ms; 0 means the default, vendor-specific limit
+	pub recovery_time_limit: u16,
+}
+
+impl ModePage for ErrorRecovery {
+	fn code() -> (u8, u8) { (0x01, 0x00) }
+
+	fn parse(data: &[u8]) -> Option<Self> {
+		if data.len() < 10 { return None; }
+
+		Some(ErrorRecovery {
+			awre: is_set(data[0], 7),
+			arre: is_set(data[0], 6),
+			tb: is_set(data[0], 5),
+			rc: is_set(data[0], 4),
+			eer: is_set(data[0], 3),
+			per: is_set(data[0], 2),
+			dte: is_set(data[0], 1),
+			dcr: is_set(data[0], 0),
+			read_retry_count: data[1],
+			// data[2..5] are obsolete
+			lbpere: is_set(data[5], 7),
+			write_retry_count: data[6],
+			// data[7] is reserved
+			recovery_time_limit: (&data[8..10]).read_u16::<BigEndian>().unwrap(),
+		})
+	}
+
+	fn write(&self, data: &mut [u8]) {
+		set_bit(&mut data[0], 7, self.awre);
+		set_bit(&mut data[0], 6, self.arre);
+		set_bit(&mut data[0], 5, self.tb);
+		set_bit(&mut data[0], 4, self.rc);
+		set_bit(&mut data[0], 3, self.eer);
+		set_bit(&mut data[0], 2, self.per);
+		set_bit(&mut data[0], 1, self.dte);
+		set_bit(&mut data[0], 0, self.dcr);
+		data[1] = self.read_retry_count;
+		set_bit(&mut data[5], 7, self.lbpere);
+		data[6] = self.write_retry_count;
+		BigEndian::write_u16(&mut data[8..10], self.recovery_time_limit);
+	}
+}
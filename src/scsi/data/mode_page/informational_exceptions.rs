@@ -0,0 +1,147 @@
+/*!
+Informational Exceptions Control mode page (1Ch).
+
+See SPC-4, 7.5.14 Informational Exceptions Control mode page. This is what SCSI devices have instead of ATA's S.M.A.R.T. feature set toggle.
+*/
+
+use byteorder::{ByteOrder, ReadBytesExt, BigEndian};
+use std::fmt;
+
+use super::{ModePage, is_set, set_bit};
+use ata::data::id::Ternary;
+
+/// Method of Reporting Informational Exceptions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serializable", derive(Serialize))]
+pub enum MRIE {
+	/// No reporting of informational exception condition
+	NoReporting,
+	/// Asynchronous event reporting (obsolete)
+	AsyncEvent,
+	/// Establish unit attention condition
+	UnitAttention,
+	/// Conditionally generate recovered error
+	ConditionalRecoveredError,
+	/// Unconditionally generate recovered error
+	UnconditionalRecoveredError,
+	/// Generate no sense
+	NoSense,
+	/// Only report informational exception condition on request (i.e. with REQUEST SENSE or the Informational Exceptions log page)
+	OnRequest,
+	Reserved(u8),
+	VendorSpecific(u8),
+}
+
+impl MRIE {
+	pub fn from(x: u8) -> MRIE {
+		use self::MRIE::*;
+		match x {
+			0x0 => NoReporting,
+			0x1 => AsyncEvent,
+			0x2 => UnitAttention,
+			0x3 => ConditionalRecoveredError,
+			0x4 => UnconditionalRecoveredError,
+			0x5 => NoSense,
+			0x6 => OnRequest,
+			x @ 0x7 ... 0xb => Reserved(x),
+			x => VendorSpecific(x),
+		}
+	}
+
+	pub fn as_u8(&self) -> u8 {
+		use self::MRIE::*;
+		match *self {
+			NoReporting => 0x0,
+			AsyncEvent => 0x1,
+			UnitAttention => 0x2,
+			ConditionalRecoveredError => 0x3,
+			UnconditionalRecoveredError => 0x4,
+			NoSense => 0x5,
+			OnRequest => 0x6,
+			Reserved(x) | VendorSpecific(x) => x & 0b1111,
+		}
+	}
+}
+
+impl fmt::Display for MRIE {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		use self::MRIE::*;
+		match *self {
+			NoReporting => write!(f, "no reporting"),
+			AsyncEvent => write!(f, "asynchronous event reporting"),
+			UnitAttention => write!(f, "unit attention"),
+			ConditionalRecoveredError => write!(f, "conditionally generate recovered error"),
+			UnconditionalRecoveredError => write!(f, "unconditionally generate recovered error"),
+			NoSense => write!(f, "generate no sense"),
+			OnRequest => write!(f, "only report on request"),
+			Reserved(x) => write!(f, "reserved ({:x}h)", x),
+			VendorSpecific(x) => write!(f, "vendor-specific ({:x}h)", x),
+		}
+	}
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serializable", derive(Serialize))]
+pub struct InformationalExceptions {
+	/// Performance: whether device is not allowed to cause delays while doing informational exception operations
+	pub perf: bool,
+	/// Enable Background Function: whether background functions (e.g. background scan) are enabled
+	pub ebf: bool,
+	/// Enable Warning: whether to report warnings (e.g. temperature)
+	pub ewasc: bool,
+	/// Disable Exception Control: whether reporting of failure prediction threshold exceeded conditions is disabled
+	pub dexcpt: bool,
+	/// Create a test device failure at the next interval timer expiration
+	pub test: bool,
+	/// Enable Background Error: whether to report background self-test and scan errors
+	pub ebackerr: bool,
+	/// Log Errors: whether to log informational exception conditions
+	pub logerr: bool,
+	pub mrie: MRIE,
+	/// Period of reporting, in 100 ms units; 0 and ffffffffh mean vendor-specific period
+	pub interval_timer: u32,
+	/// How many times the informational exception condition might be reported; 0 means no limit
+	pub report_count: u32,
+}
+
+impl InformationalExceptions {
+	/// Tells whether reporting of informational exceptions is enabled, in the same terms as ATA S.M.A.R.T. feature set status.
+	pub fn status(&self) -> Ternary {
+		if self.dexcpt { Ternary::Disabled } else { Ternary::Enabled }
+	}
+}
+
+impl ModePage for InformationalExceptions {
+	fn code() -> (u8, u8) { (0x1c, 0x00) }
+
+	fn parse(data: &[u8]) -> Option<Self> {
+		if data.len() < 10 { return None; }
+
+		Some(InformationalExceptions {
+			perf: is_set(data[0], 7),
+			// data[0] bit 6 is reserved
+			ebf: is_set(data[0], 5),
+			ewasc: is_set(data[0], 4),
+			dexcpt: is_set(data[0], 3),
+			test: is_set(data[0], 2),
+			ebackerr: is_set(data[0], 1),
+			logerr: is_set(data[0], 0),
+			mrie: MRIE::from(data[1] & 0b1111),
+			interval_timer: (&data[2..6]).read_u32::<BigEndian>().unwrap(),
+			report_count: (&data[6..10]).read_u32::<BigEndian>().unwrap(),
+		})
+	}
+
+	fn write(&self, data: &mut [u8]) {
+		set_bit(&mut data[0], 7, self.perf);
+		set_bit(&mut data[0], 5, self.ebf);
+		set_bit(&mut data[0], 4, self.ewasc);
+		set_bit(&mut data[0], 3, self.dexcpt);
+		set_bit(&mut data[0], 2, self.test);
+		set_bit(&mut data[0], 1, self.ebackerr);
+		set_bit(&mut data[0], 0, self.logerr);
+		data[1] = (data[1] & !0b1111) + self.mrie.as_u8();
+		BigEndian::write_u32(&mut data[2..6], self.interval_timer);
+		BigEndian::write_u32(&mut data[6..10], self.report_count);
+	}
+}
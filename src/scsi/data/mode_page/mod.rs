@@ -0,0 +1,227 @@
+/*!
+Functions to parse and structs to represent SCSI mode parameters and mode pages.
+
+For more, see SPC-4, 7.5 Mode parameters.
+
+Typed representations of some of the mode pages can be found in submodules. All of them implement [trait ModePage](trait.ModePage.html), which allows to alter page fields and put them back into the raw page data.
+
+## Example
+
+```ignore
+use hdd::scsi::PageControl;
+use hdd::scsi::data::mode_page;
+use hdd::scsi::data::mode_page::ModePage;
+use hdd::scsi::data::mode_page::caching::Caching;
+
+let (_sense, data) = dev.mode_sense_10(false, true, PageControl::Current, 0x08, 0)?;
+let params = mode_page::parse_10(&data).unwrap();
+
+for page in params.pages {
+	println!("{:#?}", Caching::parse(&page.data));
+}
+```
+*/
+
+pub mod error_recovery;
+pub mod caching;
+pub mod control;
+pub mod power_condition;
+pub mod informational_exceptions;
+
+use byteorder::{ReadBytesExt, BigEndian};
+
+#[derive(Debug, Clone)]
+pub struct Page {
+	pub page: u8,
+	pub subpage: Option<u8>,
+	/// Whether this page can be saved into non-volatile memory with MODE SELECT (PS bit)
+	pub saveable: bool,
+	/// Page parameters, without the page header (that is, everything starting from byte 2 for pages in the page_0 format, or from byte 4 for pages in the sub_page format)
+	pub data: Vec<u8>,
+}
+
+impl Page {
+	/// Composes page with its header, as expected by MODE SELECT (i.e. with PS bit cleared)
+	pub fn to_bytes(&self) -> Vec<u8> {
+		let mut out = vec![];
+		match self.subpage {
+			None => {
+				out.push(self.page & 0b11_1111);
+				out.push(self.data.len() as u8);
+			},
+			Some(subpage) => {
+				out.push(0b100_0000 + (self.page & 0b11_1111));
+				out.push(subpage);
+				out.push((self.data.len() >> 8) as u8);
+				out.push((self.data.len() & 0xff) as u8);
+			},
+		}
+		out.extend_from_slice(&self.data);
+		out
+	}
+}
+
+#[derive(Debug)]
+pub struct ModeParameters {
+	/// Medium type; for direct-access block devices, this is 00h
+	pub medium_type: u8,
+	/// Device-specific parameter; for direct-access block devices, this contains WP (bit 7) and DPOFUA (bit 4) bits
+	pub device_specific: u8,
+	/// Whether block descriptors are in the long LBA format
+	pub long_lba: bool,
+	/// Raw block descriptors
+	pub block_descriptors: Vec<u8>,
+	pub pages: Vec<Page>,
+}
+
+fn parse_pages(data: &[u8]) -> Option<Vec<Page>> {
+	let mut pages = vec![];
+
+	let mut i = 0;
+	let len = data.len();
+	while i < len {
+		if i + 2 > len {
+			return None; // not enough data
+		}
+
+		let saveable = data[i] & 0b1000_0000 != 0;
+		let page = data[i] & 0b11_1111;
+
+		let (subpage, plen, hlen) = if data[i] & 0b100_0000 == 0 {
+			(None, data[i+1] as usize, 2)
+		} else {
+			if i + 4 > len {
+				return None; // not enough data
+			}
+			(Some(data[i+1]), (&data[i+2 .. i+4]).read_u16::<BigEndian>().unwrap() as usize, 4)
+		};
+
+		// skip this page's header
+		i += hlen;
+
+		if i + plen > len {
+			return None; // not enough data
+		}
+
+		pages.push(Page {
+			page,
+			subpage,
+			saveable,
+			data: data[i .. i+plen].to_vec(),
+		});
+
+		i += plen;
+	}
+
+	Some(pages)
+}
+
+// TODO return Result<>
+/// Parses data returned by MODE SENSE(6)
+pub fn parse_6(data: &[u8]) -> Option<ModeParameters> {
+	if data.len() < 4 {
+		return None;
+	}
+
+	// data[0] is Mode Data Length, not including itself
+	let len = data[0] as usize + 1;
+	let bdlen = data[3] as usize;
+
+	if data.len() < len || len < 4 + bdlen {
+		// not enough data
+		return None;
+	}
+
+	Some(ModeParameters {
+		medium_type: data[1],
+		device_specific: data[2],
+		long_lba: false,
+		block_descriptors: data[4 .. 4 + bdlen].to_vec(),
+		pages: parse_pages(&data[4 + bdlen .. len])?,
+	})
+}
+
+// TODO return Result<>
+/// Parses data returned by MODE SENSE(10)
+pub fn parse_10(data: &[u8]) -> Option<ModeParameters> {
+	if data.len() < 8 {
+		return None;
+	}
+
+	// data[0..2] is Mode Data Length, not including itself
+	let len = (&data[0..2]).read_u16::<BigEndian>().unwrap() as usize + 2;
+	let bdlen = (&data[6..8]).read_u16::<BigEndian>().unwrap() as usize;
+
+	if data.len() < len || len < 8 + bdlen {
+		// not enough data
+		return None;
+	}
+
+	Some(ModeParameters {
+		medium_type: data[2],
+		device_specific: data[3],
+		long_lba: data[4] & 1 != 0,
+		block_descriptors: data[8 .. 8 + bdlen].to_vec(),
+		pages: parse_pages(&data[8 + bdlen .. len])?,
+	})
+}
+
+/// Composes mode parameter list for MODE SELECT(6), without block descriptors.
+pub fn compose_6(pages: &[Page]) -> Vec<u8> {
+	// mode data length, medium type, device-specific parameter are all reserved for MODE SELECT, and so is the block descriptor length as we don't send any
+	let mut out = vec![0; 4];
+	for page in pages {
+		out.extend(page.to_bytes());
+	}
+	out
+}
+
+/// Composes mode parameter list for MODE SELECT(10), without block descriptors.
+pub fn compose_10(pages: &[Page]) -> Vec<u8> {
+	// see compose_6
+	let mut out = vec![0; 8];
+	for page in pages {
+		out.extend(page.to_bytes());
+	}
+	out
+}
+
+/**
+Merges page parameters `new` into `current`, only altering bits that are set in the `changeable` mask (as returned by MODE SENSE with `PageControl::Changeable`).
+
+Bytes that are not covered by either of the buffers are left intact.
+*/
+pub fn apply_changeable(current: &[u8], new: &[u8], changeable: &[u8]) -> Vec<u8> {
+	current.iter().enumerate().map(|(i, &cur)| {
+		match (new.get(i), changeable.get(i)) {
+			(Some(&new), Some(&mask)) => (cur & !mask) | (new & mask),
+			_ => cur,
+		}
+	}).collect()
+}
+
+/**
+Typed representation of a mode page.
+
+Byte offsets that implementations use are relative to the start of the page parameters (see [`Page::data`](struct.Page.html#structfield.data)), i.e. they are off by 2 or 4 bytes from those in the standards.
+*/
+pub trait ModePage: Sized {
+	/// Returns tuple of `(page, subpage)` this type represents
+	fn code() -> (u8, u8);
+	/// Interprets page parameters; returns `None` if there's not enough data.
+	fn parse(data: &[u8]) -> Option<Self>;
+	/// Puts fields back into page parameters `data`, e.g. the ones that were previously fed to `parse()`.
+	fn write(&self, data: &mut [u8]);
+}
+
+pub(crate) fn is_set(x: u8, bit: usize) -> bool {
+	x & (1<<bit) != 0
+}
+
+pub(crate) fn set_bit(x: &mut u8, bit: usize, value: bool) {
+	if value {
+		*x |= 1<<bit;
+	} else {
+		*x &= !(1<<bit);
+	}
+}
@@ -0,0 +1,85 @@
+/*!
+Power Condition mode page (1Ah).
+
+See SPC-4, 7.5.13 Power Condition mode page.
+
+Devices that conform to older standards (e.g. SPC-3) return shorter version of this page, with only idle and standby timers present; fields that are absent in such pages are represented as `None`.
+*/
+
+use byteorder::{ByteOrder, ReadBytesExt, BigEndian};
+
+use super::{ModePage, is_set, set_bit};
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serializable", derive(Serialize))]
+pub struct PowerCondition {
+	/// Power Management Background Precedence
+	pub pm_bg_precedence: u8,
+	pub standby_y: bool,
+	pub idle_c: bool,
+	pub idle_b: bool,
+	pub idle_a: bool,
+	pub standby_z: bool,
+	/// Timers are in 100 ms units
+	pub idle_a_timer: u32,
+	pub standby_z_timer: u32,
+	pub idle_b_timer: Option<u32>,
+	pub idle_c_timer: Option<u32>,
+	pub standby_y_timer: Option<u32>,
+	/// Check Condition if from Idle, Standby or Stopped power conditions: packed CCF IDLE, CCF STANDBY and CCF STOPPED fields
+	pub ccf: Option<u8>,
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+	if data.len() < offset + 4 { return None; }
+	Some((&data[offset .. offset + 4]).read_u32::<BigEndian>().unwrap())
+}
+
+fn write_u32(data: &mut [u8], offset: usize, value: Option<u32>) {
+	if let Some(value) = value {
+		if data.len() >= offset + 4 {
+			BigEndian::write_u32(&mut data[offset .. offset + 4], value);
+		}
+	}
+}
+
+impl ModePage for PowerCondition {
+	fn code() -> (u8, u8) { (0x1a, 0x00) }
+
+	fn parse(data: &[u8]) -> Option<Self> {
+		if data.len() < 10 { return None; }
+
+		Some(PowerCondition {
+			pm_bg_precedence: data[0] >> 6,
+			standby_y: is_set(data[0], 0),
+			idle_c: is_set(data[1], 3),
+			idle_b: is_set(data[1], 2),
+			idle_a: is_set(data[1], 1),
+			standby_z: is_set(data[1], 0),
+			idle_a_timer: read_u32(data, 2).unwrap(),
+			standby_z_timer: read_u32(data, 6).unwrap(),
+			idle_b_timer: read_u32(data, 10),
+			idle_c_timer: read_u32(data, 14),
+			standby_y_timer: read_u32(data, 18),
+			// data[22..37] are reserved
+			ccf: data.get(37).map(|x| x >> 2),
+		})
+	}
+
+	fn write(&self, data: &mut [u8]) {
+		data[0] = (self.pm_bg_precedence << 6) + (data[0] & 0b11_1111);
+		set_bit(&mut data[0], 0, self.standby_y);
+		set_bit(&mut data[1], 3, self.idle_c);
+		set_bit(&mut data[1], 2, self.idle_b);
+		set_bit(&mut data[1], 1, self.idle_a);
+		set_bit(&mut data[1], 0, self.standby_z);
+		write_u32(data, 2, Some(self.idle_a_timer));
+		write_u32(data, 6, Some(self.standby_z_timer));
+		write_u32(data, 10, self.idle_b_timer);
+		write_u32(data, 14, self.idle_c_timer);
+		write_u32(data, 18, self.standby_y_timer);
+		if let (Some(ccf), Some(x)) = (self.ccf, data.get_mut(37)) {
+			*x = (ccf << 2) + (*x & 0b11);
+		}
+	}
+}
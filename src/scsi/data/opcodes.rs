@@ -0,0 +1,155 @@
+/*!
+Functions to parse and structs to represent REPORT SUPPORTED OPERATION CODES and REPORT SUPPORTED TASK MANAGEMENT FUNCTIONS responses.
+
+See SPC-4, 6.35 REPORT SUPPORTED OPERATION CODES command and 6.36 REPORT SUPPORTED TASK MANAGEMENT FUNCTIONS command.
+*/
+
+use byteorder::{ReadBytesExt, BigEndian};
+
+/// Command timeouts, in seconds; 0 means timeout is not specified
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serializable", derive(Serialize))]
+pub struct Timeouts {
+	/// Minimum time the command is expected to take to process
+	pub nominal: u32,
+	/// Time the application client should wait before aborting the command
+	pub recommended: u32,
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serializable", derive(Serialize))]
+pub struct Command {
+	pub opcode: u8,
+	/// `None` if the command does not have service actions
+	pub service_action: Option<u16>,
+	pub cdb_len: u16,
+	pub timeouts: Option<Timeouts>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serializable", derive(Serialize))]
+pub enum Support {
+	/// Device is unable to tell whether command is supported
+	NotAvailable,
+	NotSupported,
+	/// Command is supported as described in the standard
+	Supported,
+	/// Command is supported, but in a vendor-specific manner
+	VendorSpecific,
+	Reserved(u8),
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serializable", derive(Serialize))]
+pub struct CommandInfo {
+	pub support: Support,
+	/// Bit mask of the CDB fields that device actually looks at, starting with the opcode; e.g. for LOG SENSE, non-zero byte 3 means that subpages are supported
+	pub cdb_usage: Vec<u8>,
+	pub timeouts: Option<Timeouts>,
+}
+
+fn parse_timeouts(data: &[u8]) -> Option<Timeouts> {
+	// descriptor length is 0Ah, followed by reserved and command-specific bytes
+	if data.len() < 12 { return None; }
+	Some(Timeouts {
+		nominal: (&data[4..8]).read_u32::<BigEndian>().unwrap(),
+		recommended: (&data[8..12]).read_u32::<BigEndian>().unwrap(),
+	})
+}
+
+/// Parses response in the all_commands format (REPORTING OPTIONS 000b); returns `None` if response is too short to contain the header.
+pub fn parse_all(data: &[u8]) -> Option<Vec<Command>> {
+	if data.len() < 4 { return None; }
+
+	let len = (&data[0..4]).read_u32::<BigEndian>().unwrap() as usize;
+	let data = &data[4 .. ::std::cmp::min(data.len(), 4 + len)];
+
+	let mut commands = vec![];
+	let mut i = 0;
+	while i + 8 <= data.len() {
+		let d = &data[i .. i + 8];
+		let ctdp = d[5] & 0b10 != 0;
+
+		let timeouts = if ctdp {
+			let timeouts = parse_timeouts(&data[i + 8 ..]);
+			if timeouts.is_none() { break; } // truncated
+			timeouts
+		} else { None };
+
+		commands.push(Command {
+			opcode: d[0],
+			service_action: if d[5] & 0b1 != 0 {
+				Some((&d[2..4]).read_u16::<BigEndian>().unwrap())
+			} else { None },
+			cdb_len: (&d[6..8]).read_u16::<BigEndian>().unwrap(),
+			timeouts: timeouts,
+		});
+
+		i += if ctdp { 8 + 12 } else { 8 };
+	}
+
+	Some(commands)
+}
+
+/// Parses response in the one_command format (REPORTING OPTIONS 001b–011b); returns `None` if response is too short.
+pub fn parse_one(data: &[u8]) -> Option<CommandInfo> {
+	if data.len() < 4 { return None; }
+
+	let cdb_len = (&data[2..4]).read_u16::<BigEndian>().unwrap() as usize;
+	if data.len() < 4 + cdb_len { return None; }
+
+	Some(CommandInfo {
+		support: {
+			use self::Support::*;
+			match data[1] & 0b111 {
+				0b000 => NotAvailable,
+				0b001 => NotSupported,
+				0b011 => Supported,
+				0b101 => VendorSpecific,
+				x => Reserved(x),
+			}
+		},
+		cdb_usage: data[4 .. 4 + cdb_len].to_vec(),
+		timeouts: if data[1] & 0b1000_0000 != 0 {
+			parse_timeouts(&data[4 + cdb_len ..])
+		} else { None },
+	})
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serializable", derive(Serialize))]
+pub struct TaskManagementFunctions {
+	pub abort_task: bool,
+	pub abort_task_set: bool,
+	pub clear_aca: bool,
+	pub clear_task_set: bool,
+	pub logical_unit_reset: bool,
+	pub query_task: bool,
+	/// Obsolete
+	pub target_reset: bool,
+	/// Obsolete
+	pub wakeup: bool,
+	pub query_unit_attention: bool,
+	pub query_task_set: bool,
+	pub it_nexus_reset: bool,
+}
+
+/// Parses REPORT SUPPORTED TASK MANAGEMENT FUNCTIONS response; returns `None` if response is too short.
+pub fn parse_task_management(data: &[u8]) -> Option<TaskManagementFunctions> {
+	if data.len() < 2 { return None; }
+
+	let is_set = |x: u8, bit: usize| x & (1 << bit) != 0;
+	Some(TaskManagementFunctions {
+		abort_task: is_set(data[0], 7),
+		abort_task_set: is_set(data[0], 6),
+		clear_aca: is_set(data[0], 5),
+		clear_task_set: is_set(data[0], 4),
+		logical_unit_reset: is_set(data[0], 3),
+		query_task: is_set(data[0], 2),
+		target_reset: is_set(data[0], 1),
+		wakeup: is_set(data[0], 0),
+		query_unit_attention: is_set(data[1], 2),
+		query_task_set: is_set(data[1], 1),
+		it_nexus_reset: is_set(data[1], 0),
+	})
+}
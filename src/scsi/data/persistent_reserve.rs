@@ -0,0 +1,298 @@
+/*!
+Functions to parse and structs to represent PERSISTENT RESERVE IN responses.
+
+See SPC-4, 6.15 PERSISTENT RESERVE IN command, and 7.6.4 TransportID identifiers.
+*/
+
+use byteorder::{ReadBytesExt, BigEndian};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serializable", derive(Serialize))]
+pub enum ReservationType {
+	WriteExclusive,
+	ExclusiveAccess,
+	WriteExclusiveRegistrantsOnly,
+	ExclusiveAccessRegistrantsOnly,
+	WriteExclusiveAllRegistrants,
+	ExclusiveAccessAllRegistrants,
+	Reserved(u8),
+}
+
+impl ReservationType {
+	pub fn from(x: u8) -> Self {
+		use self::ReservationType::*;
+		match x {
+			0x1 => WriteExclusive,
+			0x3 => ExclusiveAccess,
+			0x5 => WriteExclusiveRegistrantsOnly,
+			0x6 => ExclusiveAccessRegistrantsOnly,
+			0x7 => WriteExclusiveAllRegistrants,
+			0x8 => ExclusiveAccessAllRegistrants,
+			x => Reserved(x),
+		}
+	}
+}
+
+/// Response to READ KEYS service action
+#[derive(Debug)]
+#[cfg_attr(feature = "serializable", derive(Serialize))]
+pub struct Keys {
+	/// Persistent reservations generation; incremented every time registrations change
+	pub generation: u32,
+	/// Reservation keys of all the registered I_T nexuses
+	pub keys: Vec<u64>,
+}
+
+/// Persistent reservation currently held
+#[derive(Debug)]
+#[cfg_attr(feature = "serializable", derive(Serialize))]
+pub struct Reservation {
+	/// Reservation key of the reservation holder
+	pub key: u64,
+	/// Scope of the reservation; 0h means the whole logical unit, the only scope that is not obsolete
+	pub scope: u8,
+	pub reservation_type: ReservationType,
+}
+
+/// Response to READ RESERVATION service action
+#[derive(Debug)]
+#[cfg_attr(feature = "serializable", derive(Serialize))]
+pub struct ReservationStatus {
+	pub generation: u32,
+	/// `None` if there is no persistent reservation
+	pub reservation: Option<Reservation>,
+}
+
+/// Response to REPORT CAPABILITIES service action
+#[derive(Debug)]
+#[cfg_attr(feature = "serializable", derive(Serialize))]
+pub struct Capabilities {
+	/// Replace lost reservation capable
+	pub rlr_c: bool,
+	/// Compatible reservation handling (i.e. RESERVE/RELEASE commands are handled as SPC-2 says)
+	pub crh: bool,
+	/// Specify initiator ports capable
+	pub sip_c: bool,
+	/// All target ports capable
+	pub atp_c: bool,
+	/// Persist through power loss capable
+	pub ptpl_c: bool,
+	/// Whether `types` is valid
+	pub tmv: bool,
+	/// What commands are allowed when there's a write exclusive reservation
+	pub allow_commands: u8,
+	/// Persist through power loss activated
+	pub ptpl_a: bool,
+	/// Reservation types that device supports
+	pub types: Vec<ReservationType>,
+}
+
+/// Identifies the initiator port
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serializable", derive(Serialize))]
+pub enum TransportID {
+	/// N_Port_Name
+	FibreChannel(u64),
+	/// EUI-64
+	IEEE1394(u64),
+	/// Initiator port identifier
+	SRP(Vec<u8>),
+	ISCSI {
+		name: String,
+		/// Initiator session ID, as reported in the TransportID of the `iSCSI name,i,0xISID` form
+		isid: Option<String>,
+	},
+	SAS(u64),
+	/// TransportID of the other protocols, or of the formats that are not decoded
+	Other {
+		protocol: u8,
+		format: u8,
+		data: Vec<u8>,
+	},
+}
+
+impl TransportID {
+	/// Parses TransportID at the start of `data`; returns TransportID and its length, or `None` if it is truncated.
+	pub fn parse(data: &[u8]) -> Option<(Self, usize)> {
+		if data.is_empty() { return None; }
+
+		let format = data[0] >> 6;
+		let protocol = data[0] & 0b1111;
+
+		let len = match protocol {
+			// iSCSI TransportIDs are of variable length
+			0x5 => {
+				if data.len() < 4 { return None; }
+				4 + (&data[2..4]).read_u16::<BigEndian>().unwrap() as usize
+			},
+			_ => 24,
+		};
+		if data.len() < len { return None; }
+		let d = &data[..len];
+
+		use self::TransportID::*;
+		let id = match (protocol, format) {
+			(0x0, 0) => FibreChannel((&d[8..16]).read_u64::<BigEndian>().unwrap()),
+			(0x3, 0) => IEEE1394((&d[8..16]).read_u64::<BigEndian>().unwrap()),
+			(0x4, 0) => SRP(d[8..24].to_vec()),
+			(0x5, 0) | (0x5, 1) => {
+				let s = String::from_utf8_lossy(&d[4..]);
+				let s = s.trim_matches('\0');
+				let (name, isid) = match (format, s.find(",i,0x")) {
+					(1, Some(pos)) => (&s[..pos], Some(s[pos + 5 ..].to_string())),
+					_ => (s, None),
+				};
+				ISCSI { name: name.to_string(), isid: isid }
+			},
+			(0x6, 0) => SAS((&d[4..12]).read_u64::<BigEndian>().unwrap()),
+			_ => Other { protocol: protocol, format: format, data: d.to_vec() },
+		};
+
+		Some((id, len))
+	}
+}
+
+/// Registrant, as returned by READ FULL STATUS service action
+#[derive(Debug)]
+#[cfg_attr(feature = "serializable", derive(Serialize))]
+pub struct Registrant {
+	pub key: u64,
+	/// Registration applies to all the target ports
+	pub all_target_ports: bool,
+	/// Registrant holds the reservation; `scope` and `reservation_type` are only meaningful if it does
+	pub reservation_holder: bool,
+	pub scope: u8,
+	pub reservation_type: ReservationType,
+	pub relative_target_port: u16,
+	/// `None` if TransportID is truncated
+	pub transport_id: Option<TransportID>,
+}
+
+/// Response to READ FULL STATUS service action
+#[derive(Debug)]
+#[cfg_attr(feature = "serializable", derive(Serialize))]
+pub struct FullStatus {
+	pub generation: u32,
+	pub registrants: Vec<Registrant>,
+}
+
+/// Returns `(generation, list)` from the header of READ KEYS, READ RESERVATION and READ FULL STATUS responses.
+fn parse_header(data: &[u8]) -> Option<(u32, &[u8])> {
+	if data.len() < 8 { return None; }
+
+	let generation = (&data[0..4]).read_u32::<BigEndian>().unwrap();
+	let len = (&data[4..8]).read_u32::<BigEndian>().unwrap() as usize;
+	let end = ::std::cmp::min(data.len(), len.saturating_add(8));
+
+	Some((generation, &data[8..end]))
+}
+
+/// Returns length of the whole READ KEYS, READ RESERVATION or READ FULL STATUS response, header included.
+pub fn response_len(data: &[u8]) -> Option<u32> {
+	if data.len() < 8 { return None; }
+	Some((&data[4..8]).read_u32::<BigEndian>().unwrap().saturating_add(8))
+}
+
+pub fn parse_keys(data: &[u8]) -> Option<Keys> {
+	let (generation, data) = match parse_header(data) {
+		Some(x) => x,
+		None => return None,
+	};
+
+	Some(Keys {
+		generation: generation,
+		keys: data.chunks(8)
+			.filter(|k| k.len() == 8)
+			.map(|mut k| k.read_u64::<BigEndian>().unwrap())
+			.collect(),
+	})
+}
+
+pub fn parse_reservation(data: &[u8]) -> Option<ReservationStatus> {
+	let (generation, data) = match parse_header(data) {
+		Some(x) => x,
+		None => return None,
+	};
+
+	let reservation = if data.len() >= 16 {
+		Some(Reservation {
+			key: (&data[0..8]).read_u64::<BigEndian>().unwrap(),
+			scope: data[13] >> 4,
+			reservation_type: ReservationType::from(data[13] & 0b1111),
+		})
+	} else { None };
+
+	Some(ReservationStatus {
+		generation: generation,
+		reservation: reservation,
+	})
+}
+
+fn is_set(x: u8, bit: usize) -> bool {
+	x & (1<<bit) != 0
+}
+
+pub fn parse_capabilities(data: &[u8]) -> Option<Capabilities> {
+	if data.len() < 6 { return None; }
+
+	let mut types = vec![];
+	{
+		use self::ReservationType::*;
+		for &(byte, bit, t) in &[
+			(4, 7, WriteExclusiveAllRegistrants),
+			(4, 6, ExclusiveAccessRegistrantsOnly),
+			(4, 5, WriteExclusiveRegistrantsOnly),
+			(4, 3, ExclusiveAccess),
+			(4, 1, WriteExclusive),
+			(5, 0, ExclusiveAccessAllRegistrants),
+		] {
+			if is_set(data[byte], bit) {
+				types.push(t);
+			}
+		}
+	}
+
+	Some(Capabilities {
+		rlr_c: is_set(data[2], 7),
+		crh: is_set(data[2], 4),
+		sip_c: is_set(data[2], 3),
+		atp_c: is_set(data[2], 2),
+		ptpl_c: is_set(data[2], 0),
+		tmv: is_set(data[3], 7),
+		allow_commands: (data[3] >> 4) & 0b111,
+		ptpl_a: is_set(data[3], 0),
+		types: types,
+	})
+}
+
+pub fn parse_full_status(data: &[u8]) -> Option<FullStatus> {
+	let (generation, data) = match parse_header(data) {
+		Some(x) => x,
+		None => return None,
+	};
+
+	let mut registrants = vec![];
+	let mut i = 0;
+	while i + 24 <= data.len() {
+		let d = &data[i..];
+		let len = (&d[20..24]).read_u32::<BigEndian>().unwrap() as usize;
+		let end = ::std::cmp::min(d.len(), len.saturating_add(24));
+
+		registrants.push(Registrant {
+			key: (&d[0..8]).read_u64::<BigEndian>().unwrap(),
+			all_target_ports: is_set(d[12], 1),
+			reservation_holder: is_set(d[12], 0),
+			scope: d[13] >> 4,
+			reservation_type: ReservationType::from(d[13] & 0b1111),
+			relative_target_port: (&d[18..20]).read_u16::<BigEndian>().unwrap(),
+			transport_id: TransportID::parse(&d[24..end]).map(|(id, _)| id),
+		});
+
+		i = i.saturating_add(24).saturating_add(len);
+	}
+
+	Some(FullStatus {
+		generation: generation,
+		registrants: registrants,
+	})
+}
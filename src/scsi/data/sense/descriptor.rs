@@ -1,19 +1,95 @@
+use byteorder::{ReadBytesExt, BigEndian};
+use ata::RegistersRead;
+use super::sks::{self, SenseKeySpecific};
+
 #[derive(Debug)]
-pub struct Descriptor<'a> {
+pub struct Descriptor {
 	/// Descriptor Type
 	pub code: u8,
 	/// Descriptor Specific Data
-	pub data: &'a [u8],
+	pub data: Vec<u8>,
 }
 
+/// Decoded sense data descriptor; see SPC-4, 4.5.2 Descriptor format sense data
 #[derive(Debug)]
-pub struct DescriptorData<'a> {
+pub enum DescriptorType {
+	/// Information (00h), e.g. LBA of the failed block; `None` if device indicates that information field is not valid
+	Information(Option<u64>),
+	/// Command-specific information (01h)
+	CommandSpecific(u64),
+	/// Sense key specific (02h)
+	SenseKeySpecific(SenseKeySpecific),
+	/// Field replaceable unit code (03h); 0 means no specific unit has been identified to have failed
+	FieldReplaceableUnit(u8),
+	/// Block commands (05h)
+	BlockCommands { incorrect_length: bool },
+	/// ATA Status Return (09h), i.e. ATA registers after ATA PASS-THROUGH; `*_ext` fields are only meaningful if `extend` is set
+	ATAStatusReturn {
+		extend: bool,
+		registers: RegistersRead,
+		sector_count_ext: u8,
+		sector_ext: u8,
+		cyl_low_ext: u8,
+		cyl_high_ext: u8,
+	},
+	/// Progress indication (0Ah) of an operation other than the one that sense is about, along with its sense key, ASC and ASCQ
+	ProgressIndication { key: u8, asc: u8, ascq: u8, progress: u16 },
+	/// Unknown descriptor type, or descriptor that is too short to decode
+	Other(u8),
+}
+
+impl Descriptor {
+	/// Decodes this descriptor; `key` is the sense key of the sense data this descriptor belongs to
+	pub fn decode(&self, key: u8) -> DescriptorType {
+		use self::DescriptorType::*;
+
+		let d = &self.data;
+		match (self.code, d.len()) {
+			(0x00, 10) => Information(if d[0] & 0b1000_0000 != 0 {
+				Some((&d[2..10]).read_u64::<BigEndian>().unwrap())
+			} else { None }),
+			(0x01, 10) => CommandSpecific((&d[2..10]).read_u64::<BigEndian>().unwrap()),
+			(0x02, 6) => match sks::parse(key, &d[2..5]) {
+				Some(sks) => SenseKeySpecific(sks),
+				None => Other(self.code),
+			},
+			(0x03, 2) => FieldReplaceableUnit(d[1]),
+			(0x05, 2) => BlockCommands { incorrect_length: d[1] & 0b0010_0000 != 0 },
+			(0x09, 12) => ATAStatusReturn {
+				extend: d[0] & 0b1 != 0,
+				registers: RegistersRead {
+					error: d[1],
+					sector_count: d[3],
+					sector: d[5],
+					cyl_low: d[7],
+					cyl_high: d[9],
+					device: d[10],
+					status: d[11],
+				},
+				sector_count_ext: d[2],
+				sector_ext: d[4],
+				cyl_low_ext: d[6],
+				cyl_high_ext: d[8],
+			},
+			(0x0a, 6) => ProgressIndication {
+				key: d[0] & 0b1111,
+				asc: d[1],
+				ascq: d[2],
+				progress: (&d[4..6]).read_u16::<BigEndian>().unwrap(),
+			},
+			(code, _) => Other(code),
+		}
+	}
+}
+
+#[derive(Debug)]
+pub struct DescriptorData {
 	pub key: u8,
 	/// Additional Sense Code
 	pub asc: u8,
 	/// Additional Sense Code Qualifier
 	pub ascq: u8,
-	pub descriptors: Vec<Descriptor<'a>>,
+	pub descriptors: Vec<Descriptor>,
 }
 
 pub fn parse(data: &[u8]) -> Option<DescriptorData> {
@@ -22,7 +98,7 @@ pub fn parse(data: &[u8]) -> Option<DescriptorData> {
 	}
 
 	// data[7] is Additional Sense Length, starting from data[8],
-	let len = (data[7] + 8) as usize;
+	let len = (data[7] as usize) + 8;
 	let mut descriptors = vec![];
 
 	if data.len() < len {
@@ -32,16 +108,19 @@ pub fn parse(data: &[u8]) -> Option<DescriptorData> {
 
 	// iterate over descriptors
 	let mut current_desc: usize = 8;
-	while current_desc < len {
+	while current_desc + 2 <= len {
 		let (code, dlen) = (data[current_desc], data[current_desc + 1]);
 		let dlen = dlen as usize;
 
 		// skip this descriptors' header
 		current_desc += 2;
 
+		// truncated descriptor
+		if current_desc + dlen > len { break; }
+
 		descriptors.push(Descriptor {
 			code: code,
-			data: &data[current_desc .. current_desc+dlen],
+			data: data[current_desc .. current_desc+dlen].to_vec(),
 		});
 
 		current_desc += dlen;
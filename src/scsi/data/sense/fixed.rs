@@ -1,28 +1,27 @@
 #[derive(Debug)]
-pub enum FixedData<'a> {
-	Valid {
-		/// Used in SSC-2 READ and SPACE commands
-		file_mark: bool,
-		/// End of Medium; used in SSC-2 READ, SPACE, and WRITE commands
-		eom: bool,
-		/// Used in SBC-2 READ LONG, SBC-2 WRITE LONG, and SSC-2 READ commands
-		incorrect_length: bool,
-		key: u8,
-		info: [u8; 4],
-		/// Command-Specific Information
-		cmd_info: [u8; 4],
-		/// Additional Sense Code
-		asc: u8,
-		/// Additional Sense Code Qualifier
-		ascq: u8,
-		/// Field Replaceable Unit Code
-		fruc: u8,
-		/// Sense Key Specific (including the Sense Key Specific Valid leading bit)
-		sks: [u8; 3],
-		/// Additional Sense Bytes
-		more: &'a [u8],
-	},
-	Invalid(&'a [u8]),
+pub struct FixedData {
+	/// Used in SSC-2 READ and SPACE commands
+	pub file_mark: bool,
+	/// End of Medium; used in SSC-2 READ, SPACE, and WRITE commands
+	pub eom: bool,
+	/// Used in SBC-2 READ LONG, SBC-2 WRITE LONG, and SSC-2 READ commands
+	pub incorrect_length: bool,
+	pub key: u8,
+	/// Whether `info` field contains valid information (VALID bit)
+	pub info_valid: bool,
+	pub info: [u8; 4],
+	/// Command-Specific Information
+	pub cmd_info: [u8; 4],
+	/// Additional Sense Code
+	pub asc: u8,
+	/// Additional Sense Code Qualifier
+	pub ascq: u8,
+	/// Field Replaceable Unit Code
+	pub fruc: u8,
+	/// Sense Key Specific (including the Sense Key Specific Valid leading bit)
+	pub sks: [u8; 3],
+	/// Additional Sense Bytes
+	pub more: Vec<u8>,
 }
 
 fn copy_from_slice_3(x: &[u8]) -> [u8; 3] {
@@ -40,19 +39,17 @@ pub fn parse(data: &[u8]) -> Option<FixedData> {
 	if data.len() < 18 {
 		return None;
 	}
-	if data[0] & 0b1000_0000 != 0 {
-		return Some(FixedData::Invalid(data));
-	}
 
 	// data[7] is Additional Sense Length, starting from data[8],
-	let len = (data[7] + 8) as usize;
+	let len = (data[7] as usize) + 8;
 
-	Some(FixedData::Valid {
+	Some(FixedData {
 		file_mark: data[2] & 0b1000_0000 != 0,
 		eom: data[2] & 0b0100_0000 != 0,
 		incorrect_length: data[2] & 0b0010_0000 != 0,
 		key: data[2] & 0b1111,
 
+		info_valid: data[0] & 0b1000_0000 != 0,
 		info: copy_from_slice_4(&data[3..7]),
 		cmd_info: copy_from_slice_4(&data[8..12]),
 		asc: data[12],
@@ -63,8 +60,10 @@ pub fn parse(data: &[u8]) -> Option<FixedData> {
 		more: if len > data.len() {
 			// sense reports more data than `data` buffer actually fits
 			return None
+		} else if len < 18 {
+			vec![]
 		} else {
-			&data[18 .. len]
+			data[18 .. len].to_vec()
 		}
 	})
 }
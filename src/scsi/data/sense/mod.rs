@@ -4,12 +4,130 @@ pub use self::fixed::FixedData;
 pub mod key;
 
 mod descriptor;
-pub use self::descriptor::{Descriptor, DescriptorData};
+pub use self::descriptor::{Descriptor, DescriptorType, DescriptorData};
+
+mod sks;
+pub use self::sks::SenseKeySpecific;
+
+use byteorder::{ReadBytesExt, BigEndian};
 
 #[derive(Debug)]
-pub enum Sense<'a> {
-	Fixed(FixedData<'a>),
-	Descriptor(DescriptorData<'a>),
+pub enum Sense {
+	Fixed(FixedData),
+	Descriptor(DescriptorData),
+}
+
+/// Power condition that device reports with the LOW POWER CONDITION ON family of additional sense codes (ASC 5Eh)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PowerCondition {
+	/// Device is in some low power condition, but does not tell which
+	LowPower,
+	/// `by_timer` tells whether this condition was activated by the timer or by the command
+	IdleA { by_timer: bool },
+	IdleB { by_timer: bool },
+	IdleC { by_timer: bool },
+	StandbyZ { by_timer: bool },
+	StandbyY { by_timer: bool },
+}
+
+impl Sense {
+	/// Returns tuple of `(sense key, additional sense code, additional sense code qualifier)`
+	pub fn code(&self) -> (u8, u8, u8) {
+		match *self {
+			Sense::Fixed(FixedData { key, asc, ascq, .. })
+			| Sense::Descriptor(DescriptorData { key, asc, ascq, .. })
+			=> (key, asc, ascq),
+		}
+	}
+
+	/// Returns power condition that device is in, if sense reports any
+	pub fn power_condition(&self) -> Option<PowerCondition> {
+		use self::PowerCondition::*;
+		match self.code() {
+			(_, 0x5e, ascq) => match ascq {
+				0x00 => Some(LowPower),
+				0x01 => Some(IdleA { by_timer: true }),
+				0x02 => Some(StandbyZ { by_timer: true }),
+				0x03 => Some(IdleA { by_timer: false }),
+				0x04 => Some(StandbyZ { by_timer: false }),
+				0x05 => Some(IdleB { by_timer: true }),
+				0x06 => Some(IdleB { by_timer: false }),
+				0x07 => Some(IdleC { by_timer: true }),
+				0x08 => Some(IdleC { by_timer: false }),
+				0x09 => Some(StandbyY { by_timer: true }),
+				0x0a => Some(StandbyY { by_timer: false }),
+				_ => None,
+			},
+			_ => None,
+		}
+	}
+
+	/**
+	Returns decoded sense data descriptors.
+
+	Fields of the fixed format sense are represented as the equivalent descriptors, so this works the same way for both formats. Fields that do not carry any information (e.g. invalid sense-key specific data or zero FRU code) are omitted.
+	*/
+	pub fn descriptors(&self) -> Vec<DescriptorType> {
+		match *self {
+			Sense::Fixed(ref data) => {
+				let mut descriptors = vec![];
+
+				if data.info_valid {
+					descriptors.push(DescriptorType::Information(Some((&data.info[..]).read_u32::<BigEndian>().unwrap() as u64)));
+				}
+				let cmd_info = (&data.cmd_info[..]).read_u32::<BigEndian>().unwrap();
+				if cmd_info != 0 {
+					descriptors.push(DescriptorType::CommandSpecific(cmd_info as u64));
+				}
+				if let Some(sks) = sks::parse(data.key, &data.sks) {
+					descriptors.push(DescriptorType::SenseKeySpecific(sks));
+				}
+				if data.fruc != 0 {
+					descriptors.push(DescriptorType::FieldReplaceableUnit(data.fruc));
+				}
+				if data.incorrect_length {
+					descriptors.push(DescriptorType::BlockCommands { incorrect_length: true });
+				}
+
+				descriptors
+			},
+			Sense::Descriptor(ref data) => data.descriptors.iter()
+				.map(|d| d.decode(data.key))
+				.collect(),
+		}
+	}
+
+	/// Returns the information field (e.g. LBA of the block that caused an error), if it is valid
+	pub fn information(&self) -> Option<u64> {
+		self.descriptors().into_iter()
+			.filter_map(|d| match d {
+				DescriptorType::Information(info) => info,
+				_ => None,
+			})
+			.next()
+	}
+
+	/// Returns sense-key specific data (e.g. progress indication or the pointer to the invalid field of the CDB), if it is valid
+	pub fn sense_key_specific(&self) -> Option<SenseKeySpecific> {
+		self.descriptors().into_iter()
+			.filter_map(|d| match d {
+				DescriptorType::SenseKeySpecific(sks) => Some(sks),
+				_ => None,
+			})
+			.next()
+	}
+
+	/**
+	Returns progress indication of the current operation (e.g. self-test or FORMAT UNIT), as the numerator of a fraction whose denominator is 65536.
+
+	Returns `None` if there's no progress indication in the sense-key specific data, or if the sense key is neither No Sense nor Not Ready.
+	*/
+	pub fn progress(&self) -> Option<u16> {
+		match self.sense_key_specific() {
+			Some(SenseKeySpecific::Progress(progress)) => Some(progress),
+			_ => None,
+		}
+	}
 }
 
 /**
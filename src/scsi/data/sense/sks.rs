@@ -0,0 +1,63 @@
+/// Sense-key specific data; its meaning depends on the sense key. See SPC-4, 4.5.2.4 Sense key specific sense data descriptor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SenseKeySpecific {
+	/// Illegal Request: points to the field (and optionally the bit) in the CDB or in the parameter list that caused an error
+	FieldPointer {
+		/// Whether the error is in the CDB (`true`) or in the parameter list (`false`)
+		in_cdb: bool,
+		bit_pointer: Option<u8>,
+		field_pointer: u16,
+	},
+	/// Recovered Error, Medium Error, Hardware Error: number of retries of the recovery algorithm
+	ActualRetryCount(u16),
+	/// No Sense, Not Ready: progress of the current operation, as the numerator of a fraction whose denominator is 65536
+	Progress(u16),
+	/// Copy Aborted: points to the byte (and optionally the bit) of the segment descriptor or parameter list that caused an error
+	SegmentPointer {
+		/// Whether field pointer is relative to the start of the segment descriptor (`true`) or to the start of the parameter list (`false`)
+		segment_descriptor: bool,
+		bit_pointer: Option<u8>,
+		field_pointer: u16,
+	},
+	/// Unit Attention: whether the unit attention condition queue has overflowed
+	UnitAttentionQueueOverflow(bool),
+	/// Sense key does not define any sense-key specific data
+	Reserved([u8; 3]),
+}
+
+impl SenseKeySpecific {
+	/// Progress of the current operation, in percent; `None` if this is not a progress indication
+	pub fn percent(&self) -> Option<f32> {
+		match *self {
+			SenseKeySpecific::Progress(p) => Some(p as f32 * 100. / 65536.),
+			_ => None,
+		}
+	}
+}
+
+/// Parses sense-key specific field `sks` (3 bytes, including the SKSV bit) according to the sense key `key`; returns `None` if the SKSV bit is not set.
+pub fn parse(key: u8, sks: &[u8]) -> Option<SenseKeySpecific> {
+	use self::SenseKeySpecific::*;
+
+	if sks.len() < 3 || sks[0] & 0b1000_0000 == 0 { return None; }
+
+	let value = ((sks[1] as u16) << 8) + sks[2] as u16;
+	let bit_pointer = if sks[0] & 0b1000 != 0 { Some(sks[0] & 0b111) } else { None };
+
+	Some(match key {
+		0x00 | 0x02 => Progress(value),
+		0x01 | 0x03 | 0x04 => ActualRetryCount(value),
+		0x05 => FieldPointer {
+			in_cdb: sks[0] & 0b0100_0000 != 0,
+			bit_pointer: bit_pointer,
+			field_pointer: value,
+		},
+		0x06 => UnitAttentionQueueOverflow(sks[0] & 0b1 != 0),
+		0x0a => SegmentPointer {
+			segment_descriptor: sks[0] & 0b0010_0000 != 0,
+			bit_pointer: bit_pointer,
+			field_pointer: value,
+		},
+		_ => Reserved([sks[0], sks[1], sks[2]]),
+	})
+}
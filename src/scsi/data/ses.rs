@@ -0,0 +1,658 @@
+/*!
+Functions to parse and structs to represent SCSI Enclosure Services (SES) diagnostic pages.
+
+See SES-3, 6.1 SES diagnostic pages, and 7 Element definitions.
+
+All the pages but Configuration (01h) are interpreted with the help of the configuration page, which describes what elements enclosure has. Elements are listed in the order they appear in the status pages: for each type descriptor header, overall element goes first, followed by the individual elements of that type.
+*/
+
+use byteorder::{ReadBytesExt, ByteOrder, BigEndian};
+
+use super::ascii;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serializable", derive(Serialize))]
+pub enum ElementType {
+	Unspecified,
+	DeviceSlot,
+	PowerSupply,
+	Cooling,
+	TemperatureSensor,
+	Door,
+	AudibleAlarm,
+	EnclosureServicesController,
+	SCCController,
+	NonvolatileCache,
+	InvalidOperationReason,
+	UninterruptiblePowerSupply,
+	Display,
+	KeyPadEntry,
+	Enclosure,
+	SCSIPortTransceiver,
+	Language,
+	CommunicationPort,
+	VoltageSensor,
+	CurrentSensor,
+	SCSITargetPort,
+	SCSIInitiatorPort,
+	SimpleSubenclosure,
+	ArrayDeviceSlot,
+	SASExpander,
+	SASConnector,
+	VendorSpecific(u8),
+	Reserved(u8),
+}
+
+impl ElementType {
+	pub fn from(x: u8) -> Self {
+		use self::ElementType::*;
+		match x {
+			0x00 => Unspecified,
+			0x01 => DeviceSlot,
+			0x02 => PowerSupply,
+			0x03 => Cooling,
+			0x04 => TemperatureSensor,
+			0x05 => Door,
+			0x06 => AudibleAlarm,
+			0x07 => EnclosureServicesController,
+			0x08 => SCCController,
+			0x09 => NonvolatileCache,
+			0x0a => InvalidOperationReason,
+			0x0b => UninterruptiblePowerSupply,
+			0x0c => Display,
+			0x0d => KeyPadEntry,
+			0x0e => Enclosure,
+			0x0f => SCSIPortTransceiver,
+			0x10 => Language,
+			0x11 => CommunicationPort,
+			0x12 => VoltageSensor,
+			0x13 => CurrentSensor,
+			0x14 => SCSITargetPort,
+			0x15 => SCSIInitiatorPort,
+			0x16 => SimpleSubenclosure,
+			0x17 => ArrayDeviceSlot,
+			0x18 => SASExpander,
+			0x19 => SASConnector,
+			x @ 0x80 ... 0xff => VendorSpecific(x),
+			x => Reserved(x),
+		}
+	}
+
+	/// Whether elements of this type might be described in Additional Element Status page
+	fn has_additional_status(&self) -> bool {
+		use self::ElementType::*;
+		match *self {
+			DeviceSlot | ArrayDeviceSlot | SASExpander | SCSIInitiatorPort | SCSITargetPort | EnclosureServicesController => true,
+			_ => false,
+		}
+	}
+}
+
+/// Enclosure (primary or secondary subenclosure) descriptor of Configuration page
+#[derive(Debug)]
+#[cfg_attr(feature = "serializable", derive(Serialize))]
+pub struct Enclosure {
+	pub subenclosure_id: u8,
+	/// Enclosure logical identifier, usually the NAA identifier (i.e. SAS address) of the enclosure
+	pub logical_id: u64,
+	pub vendor_id: String,
+	pub product_id: String,
+	pub product_rev: String,
+}
+
+/// Type descriptor header (and text) of Configuration page
+#[derive(Debug)]
+#[cfg_attr(feature = "serializable", derive(Serialize))]
+pub struct TypeDescriptor {
+	pub element_type: ElementType,
+	/// Number of individual elements of this type (not counting overall element)
+	pub elements: u8,
+	pub subenclosure_id: u8,
+	pub text: String,
+}
+
+/// Configuration diagnostic page (01h)
+#[derive(Debug)]
+#[cfg_attr(feature = "serializable", derive(Serialize))]
+pub struct Configuration {
+	/// Changes every time enclosure configuration changes; status and control pages carry the same number
+	pub generation: u32,
+	pub enclosures: Vec<Enclosure>,
+	pub types: Vec<TypeDescriptor>,
+}
+
+/// Identifies the element of the enclosure
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serializable", derive(Serialize))]
+pub struct ElementId {
+	pub element_type: ElementType,
+	pub subenclosure_id: u8,
+	/// Index of the type descriptor header in the Configuration page
+	pub type_index: usize,
+	/// Index of the element among elements of its type; `None` for overall element
+	pub index: Option<u8>,
+	/// Index of the individual element among all the individual elements of the enclosure (i.e. the ELEMENT INDEX, with overall elements not counted); `None` for overall element
+	pub element_index: Option<u16>,
+}
+
+impl Configuration {
+	/// Lists all the elements (overall elements included) in the order they appear in status, control and element descriptor pages.
+	pub fn elements(&self) -> Vec<ElementId> {
+		let mut elements = vec![];
+		let mut element_index = 0;
+
+		for (type_index, t) in self.types.iter().enumerate() {
+			let id = |index, element_index| ElementId {
+				element_type: t.element_type,
+				subenclosure_id: t.subenclosure_id,
+				type_index: type_index,
+				index: index,
+				element_index: element_index,
+			};
+
+			elements.push(id(None, None));
+			for i in 0 .. t.elements {
+				elements.push(id(Some(i), Some(element_index)));
+				element_index += 1;
+			}
+		}
+
+		elements
+	}
+}
+
+/// Checks page header, returning `(generation, page contents after generation code)`
+fn page_data(data: &[u8], page: u8) -> Option<(u32, &[u8])> {
+	if data.len() < 8 || data[0] != page { return None; }
+
+	let len = (&data[2..4]).read_u16::<BigEndian>().unwrap() as usize;
+	let end = ::std::cmp::min(data.len(), 4 + len);
+	if end < 8 { return None; }
+
+	Some(((&data[4..8]).read_u32::<BigEndian>().unwrap(), &data[8 .. end]))
+}
+
+/// Parses Configuration diagnostic page; returns `None` if page is malformed.
+pub fn parse_configuration(data: &[u8]) -> Option<Configuration> {
+	if data.len() < 2 { return None; }
+	let secondary = data[1] as usize;
+
+	let (generation, data) = match page_data(data, 0x01) {
+		Some(x) => x,
+		None => return None,
+	};
+
+	let mut enclosures = vec![];
+	let mut headers = 0;
+	let mut i = 0;
+	for _ in 0 .. secondary + 1 {
+		if i + 4 > data.len() { return None; }
+		let len = data[i + 3] as usize + 4;
+		if i + len > data.len() || len < 40 { return None; }
+		let d = &data[i .. i + len];
+
+		headers += d[2] as usize;
+		enclosures.push(Enclosure {
+			subenclosure_id: d[1],
+			logical_id: (&d[4..12]).read_u64::<BigEndian>().unwrap(),
+			vendor_id: ascii(&d[12..20]),
+			product_id: ascii(&d[20..36]),
+			product_rev: ascii(&d[36..40]),
+		});
+
+		i += len;
+	}
+
+	if i + headers * 4 > data.len() { return None; }
+	let mut types: Vec<_> = data[i .. i + headers * 4].chunks(4)
+		.map(|d| (ElementType::from(d[0]), d[1], d[2], d[3] as usize))
+		.collect();
+	i += headers * 4;
+
+	let types = types.drain(..).map(|(element_type, elements, subenclosure_id, text_len)| {
+		let start = ::std::cmp::min(i, data.len());
+		let end = ::std::cmp::min(i + text_len, data.len());
+		i += text_len;
+
+		TypeDescriptor {
+			element_type: element_type,
+			elements: elements,
+			subenclosure_id: subenclosure_id,
+			text: ascii(&data[start .. end]),
+		}
+	}).collect();
+
+	Some(Configuration {
+		generation: generation,
+		enclosures: enclosures,
+		types: types,
+	})
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serializable", derive(Serialize))]
+pub enum StatusCode {
+	Unsupported,
+	OK,
+	Critical,
+	NonCritical,
+	Unrecoverable,
+	NotInstalled,
+	Unknown,
+	/// Element is installed, but not turned on or configured
+	NotAvailable,
+	NoAccessAllowed,
+	Reserved(u8),
+}
+
+impl StatusCode {
+	fn from(x: u8) -> Self {
+		use self::StatusCode::*;
+		match x {
+			0x0 => Unsupported,
+			0x1 => OK,
+			0x2 => Critical,
+			0x3 => NonCritical,
+			0x4 => Unrecoverable,
+			0x5 => NotInstalled,
+			0x6 => Unknown,
+			0x7 => NotAvailable,
+			0x8 => NoAccessAllowed,
+			x => Reserved(x),
+		}
+	}
+}
+
+/// Type-specific part of the status element
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serializable", derive(Serialize))]
+pub enum Details {
+	/// Device slot or array device slot
+	DeviceSlot {
+		/// Identify LED is on
+		ident: bool,
+		/// Fault LED is on because enclosure detected a fault
+		fault_sensed: bool,
+		/// Fault LED is on because application client asked for it
+		fault_requested: bool,
+		/// Device is turned off
+		device_off: bool,
+		do_not_remove: bool,
+		ready_to_insert: bool,
+		/// Device is ready to be removed
+		remove: bool,
+	},
+	PowerSupply {
+		ident: bool,
+		dc_overvoltage: bool,
+		dc_undervoltage: bool,
+		dc_overcurrent: bool,
+		hot_swap: bool,
+		fail: bool,
+		requested_on: bool,
+		off: bool,
+		overtemperature_failure: bool,
+		temperature_warning: bool,
+		ac_fail: bool,
+		dc_fail: bool,
+	},
+	Cooling {
+		ident: bool,
+		do_not_remove: bool,
+		/// Actual fan speed, in RPM
+		rpm: u16,
+		hot_swap: bool,
+		fail: bool,
+		requested_on: bool,
+		off: bool,
+		/// 0 means stopped, 1–7 mean lowest to highest speed
+		speed_code: u8,
+	},
+	TemperatureSensor {
+		ident: bool,
+		fail: bool,
+		/// Temperature in °C, or `None` if it is not reported
+		temperature: Option<i16>,
+		overtemperature_failure: bool,
+		overtemperature_warning: bool,
+		undertemperature_failure: bool,
+		undertemperature_warning: bool,
+	},
+	VoltageSensor {
+		ident: bool,
+		fail: bool,
+		/// Voltage, in volts
+		voltage: f32,
+	},
+	CurrentSensor {
+		ident: bool,
+		fail: bool,
+		/// Current, in amperes
+		current: f32,
+	},
+	/// Raw type-specific bytes of the element types that are not decoded
+	Other([u8; 3]),
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serializable", derive(Serialize))]
+pub struct ElementStatus {
+	pub id: ElementId,
+	pub status: StatusCode,
+	/// Predicted failure
+	pub prdfail: bool,
+	pub disabled: bool,
+	/// Element has been swapped since the last time SWAP bit was reset
+	pub swap: bool,
+	pub details: Details,
+	/// Raw status element, e.g. to compose control element from
+	pub raw: [u8; 4],
+}
+
+/// Enclosure Status diagnostic page (02h)
+#[derive(Debug)]
+#[cfg_attr(feature = "serializable", derive(Serialize))]
+pub struct EnclosureStatus {
+	pub generation: u32,
+	/// Invalid operation requested
+	pub invop: bool,
+	/// Information condition
+	pub info: bool,
+	pub non_critical: bool,
+	pub critical: bool,
+	pub unrecoverable: bool,
+	pub elements: Vec<ElementStatus>,
+}
+
+fn is_set(x: u8, bit: usize) -> bool {
+	x & (1<<bit) != 0
+}
+
+fn parse_details(element_type: ElementType, d: &[u8]) -> Details {
+	use self::ElementType::*;
+	match element_type {
+		DeviceSlot | ArrayDeviceSlot => Details::DeviceSlot {
+			ident: is_set(d[2], 1),
+			fault_sensed: is_set(d[3], 6),
+			fault_requested: is_set(d[3], 5),
+			device_off: is_set(d[3], 4),
+			do_not_remove: is_set(d[2], 6),
+			ready_to_insert: is_set(d[2], 3),
+			remove: is_set(d[2], 2),
+		},
+		PowerSupply => Details::PowerSupply {
+			ident: is_set(d[1], 7),
+			dc_overvoltage: is_set(d[2], 3),
+			dc_undervoltage: is_set(d[2], 2),
+			dc_overcurrent: is_set(d[2], 1),
+			hot_swap: is_set(d[3], 7),
+			fail: is_set(d[3], 6),
+			requested_on: is_set(d[3], 5),
+			off: is_set(d[3], 4),
+			overtemperature_failure: is_set(d[3], 3),
+			temperature_warning: is_set(d[3], 2),
+			ac_fail: is_set(d[3], 1),
+			dc_fail: is_set(d[3], 0),
+		},
+		Cooling => Details::Cooling {
+			ident: is_set(d[1], 7),
+			do_not_remove: is_set(d[1], 6),
+			rpm: ((((d[1] & 0b111) as u16) << 8) + d[2] as u16) * 10,
+			hot_swap: is_set(d[3], 7),
+			fail: is_set(d[3], 6),
+			requested_on: is_set(d[3], 5),
+			off: is_set(d[3], 4),
+			speed_code: d[3] & 0b111,
+		},
+		TemperatureSensor => Details::TemperatureSensor {
+			ident: is_set(d[1], 7),
+			fail: is_set(d[1], 6),
+			// offset by 20, so it can go as low as −19°C; 0 is reserved
+			temperature: if d[2] == 0 { None } else { Some(d[2] as i16 - 20) },
+			overtemperature_failure: is_set(d[3], 3),
+			overtemperature_warning: is_set(d[3], 2),
+			undertemperature_failure: is_set(d[3], 1),
+			undertemperature_warning: is_set(d[3], 0),
+		},
+		VoltageSensor => Details::VoltageSensor {
+			ident: is_set(d[1], 7),
+			fail: is_set(d[1], 6),
+			// in 10 mV units
+			voltage: (&d[2..4]).read_i16::<BigEndian>().unwrap() as f32 / 100.,
+		},
+		CurrentSensor => Details::CurrentSensor {
+			ident: is_set(d[1], 7),
+			fail: is_set(d[1], 6),
+			// in 10 mA units
+			current: (&d[2..4]).read_i16::<BigEndian>().unwrap() as f32 / 100.,
+		},
+		_ => Details::Other([d[1], d[2], d[3]]),
+	}
+}
+
+/// Parses Enclosure Status diagnostic page according to the `config`; returns `None` if page is malformed.
+pub fn parse_enclosure_status(data: &[u8], config: &Configuration) -> Option<EnclosureStatus> {
+	if data.len() < 2 { return None; }
+	let flags = data[1];
+
+	let (generation, data) = match page_data(data, 0x02) {
+		Some(x) => x,
+		None => return None,
+	};
+
+	let elements = config.elements().into_iter()
+		.zip(data.chunks(4).filter(|d| d.len() == 4))
+		.map(|(id, d)| ElementStatus {
+			id: id,
+			status: StatusCode::from(d[0] & 0b1111),
+			prdfail: is_set(d[0], 6),
+			disabled: is_set(d[0], 5),
+			swap: is_set(d[0], 4),
+			details: parse_details(id.element_type, d),
+			raw: [d[0], d[1], d[2], d[3]],
+		})
+		.collect();
+
+	Some(EnclosureStatus {
+		generation: generation,
+		invop: is_set(flags, 4),
+		info: is_set(flags, 3),
+		non_critical: is_set(flags, 2),
+		critical: is_set(flags, 1),
+		unrecoverable: is_set(flags, 0),
+		elements: elements,
+	})
+}
+
+/// Parses Element Descriptor diagnostic page according to the `config`; returns `None` if page is malformed.
+pub fn parse_element_descriptors(data: &[u8], config: &Configuration) -> Option<Vec<(ElementId, String)>> {
+	let (_, data) = match page_data(data, 0x07) {
+		Some(x) => x,
+		None => return None,
+	};
+
+	let mut descriptors = vec![];
+	let mut i = 0;
+	for id in config.elements() {
+		if i + 4 > data.len() { break; }
+		let len = (&data[i + 2 .. i + 4]).read_u16::<BigEndian>().unwrap() as usize;
+		if i + 4 + len > data.len() { break; }
+
+		descriptors.push((id, ascii(&data[i + 4 .. i + 4 + len])));
+		i += 4 + len;
+	}
+
+	Some(descriptors)
+}
+
+/// SAS phy descriptor of Additional Element Status page
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serializable", derive(Serialize))]
+pub struct Phy {
+	pub id: u8,
+	/// Device type of the attached device: 0 for no device, 1 for end device, 2 and 3 for expanders
+	pub device_type: u8,
+	pub sata_device: bool,
+	pub ssp_target: bool,
+	pub stp_target: bool,
+	pub smp_target: bool,
+	pub sas_address: u64,
+	pub attached_sas_address: u64,
+}
+
+/// Additional Element Status descriptor of the device slot or array device slot element
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serializable", derive(Serialize))]
+pub struct AdditionalElementStatus {
+	pub id: ElementId,
+	/// Descriptor contents are invalid, e.g. the slot is empty
+	pub invalid: bool,
+	/// Protocol identifier; 6h for SAS
+	pub protocol: u8,
+	/// Slot number, as printed on the enclosure; SES-3 device slot descriptors only
+	pub slot: Option<u8>,
+	/// SAS phys of the device in the slot; SAS descriptors only
+	pub phys: Vec<Phy>,
+}
+
+fn parse_sas_phys(data: &[u8]) -> Vec<Phy> {
+	data.chunks(28)
+		.filter(|d| d.len() == 28)
+		.map(|d| Phy {
+			id: d[20],
+			device_type: (d[0] >> 4) & 0b111,
+			sata_device: is_set(d[3], 0),
+			ssp_target: is_set(d[3], 3),
+			stp_target: is_set(d[3], 2),
+			smp_target: is_set(d[3], 1),
+			attached_sas_address: (&d[4..12]).read_u64::<BigEndian>().unwrap(),
+			sas_address: (&d[12..20]).read_u64::<BigEndian>().unwrap(),
+		})
+		.collect()
+}
+
+/**
+Parses Additional Element Status diagnostic page according to the `config`; returns `None` if page is malformed.
+
+Only device slot and array device slot descriptors are returned.
+*/
+pub fn parse_additional_element_status(data: &[u8], config: &Configuration) -> Option<Vec<AdditionalElementStatus>> {
+	let (_, data) = match page_data(data, 0x0a) {
+		Some(x) => x,
+		None => return None,
+	};
+
+	let all = config.elements();
+	let individual: Vec<_> = all.iter().filter(|id| id.index.is_some()).cloned().collect();
+	// elements that are described in this page in order, for descriptors without element index
+	let mut implied = individual.iter().filter(|id| id.element_type.has_additional_status());
+
+	let mut descriptors = vec![];
+	let mut i = 0;
+	while i + 2 <= data.len() {
+		let len = data[i + 1] as usize + 2;
+		if i + len > data.len() { break; }
+		let d = &data[i .. i + len];
+		i += len;
+
+		let invalid = is_set(d[0], 7);
+		let eip = is_set(d[0], 4);
+		let protocol = d[0] & 0b1111;
+
+		let (id, info) = if eip {
+			if d.len() < 4 { continue; }
+			// EIIOE: whether element index includes overall elements
+			let id = if d[2] & 0b11 != 0 {
+				all.get(d[3] as usize)
+			} else {
+				individual.get(d[3] as usize)
+			};
+			(id.cloned(), &d[4..])
+		} else {
+			(implied.next().cloned(), &d[2..])
+		};
+
+		let id = match id {
+			Some(id) => id,
+			None => continue,
+		};
+		match id.element_type {
+			ElementType::DeviceSlot | ElementType::ArrayDeviceSlot => (),
+			_ => continue,
+		}
+
+		// descriptor type 00b, i.e. device slot; with EIP set, there's also a device slot number
+		let (slot, phys) = if protocol == 0x6 && info.len() >= 2 && info[1] >> 6 == 0 {
+			if eip {
+				if info.len() < 4 { continue; }
+				(Some(info[3]), parse_sas_phys(&info[4..]))
+			} else {
+				(None, parse_sas_phys(&info[2..]))
+			}
+		} else { (None, vec![]) };
+
+		descriptors.push(AdditionalElementStatus {
+			id: id,
+			invalid: invalid,
+			protocol: protocol,
+			slot: slot,
+			phys: phys,
+		});
+	}
+
+	Some(descriptors)
+}
+
+/// Requested state of the device slot (or array device slot) element; `None` leaves corresponding LED as is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SlotControl {
+	/// Turn identify LED on or off
+	pub ident: Option<bool>,
+	/// Turn fault LED on or off
+	pub fault: Option<bool>,
+}
+
+/**
+Composes Enclosure Control diagnostic page (02h) that changes LEDs of a single device slot or array device slot `element`, leaving all the other elements intact.
+
+Other requests of that slot (e.g. DO NOT REMOVE, DEVICE OFF, or LED that is not being changed) are carried over from its current status `element`, so that they are not reset.
+
+`generation` is the generation code as reported in Configuration or Enclosure Status page; enclosure rejects the page if configuration has changed since then.
+*/
+pub fn compose_slot_control(config: &Configuration, generation: u32, element: &ElementStatus, control: SlotControl) -> Vec<u8> {
+	let elements = config.elements();
+	let len = 4 + elements.len() * 4;
+
+	let mut data = vec![0; 4 + len];
+	data[0] = 0x02;
+	BigEndian::write_u16(&mut data[2..4], len as u16);
+	BigEndian::write_u32(&mut data[4..8], generation);
+
+	// elements with SELECT bit cleared are ignored
+	if let Some(pos) = elements.iter().position(|e| *e == element.id) {
+		let status = &element.raw;
+		let d = &mut data[8 + pos*4 .. 8 + pos*4 + 4];
+
+		// SELECT, PRDFAIL, DISABLE
+		d[0] = 0b1000_0000 | (status[0] & 0b0110_0000);
+		// array device slot: RQST OK, RQST RSVD DEVICE, … are at the same positions as their status counterparts; device slot: reserved (status has slot address here)
+		d[1] = if element.id.element_type == ElementType::ArrayDeviceSlot { status[1] } else { 0 };
+		// DO NOT REMOVE, RQST INSERT, RQST REMOVE, RQST IDENT
+		d[2] = status[2] & 0b0100_1110;
+		// RQST FAULT (FAULT REQSTD in status, not FAULT SENSED), DEVICE OFF, ENABLE BYP A, ENABLE BYP B
+		d[3] = status[3] & 0b0011_1100;
+
+		match control.ident {
+			Some(true) => d[2] |= 0b10,
+			Some(false) => d[2] &= !0b10,
+			None => (),
+		}
+		match control.fault {
+			Some(true) => d[3] |= 0b10_0000,
+			Some(false) => d[3] &= !0b10_0000,
+			None => (),
+		}
+	}
+
+	data
+}
@@ -0,0 +1,172 @@
+/*!
+Decoders for vendor-specific log pages (30h–3Eh).
+
+Meaning of these pages depends on who made the device, so decoders are matched against INQUIRY vendor and product identification. Built-in decoders are listed in [`builtin()`](fn.builtin.html); use [`SCSIPages::register_vendor_page`](../../pages/struct.SCSIPages.html#method.register_vendor_page) to add more.
+
+## Example
+
+```ignore
+use hdd::scsi::data::log_page;
+use hdd::scsi::data::vendor_log_page::VendorPage;
+use hdd::scsi::pages::SCSIPages;
+
+fn param_name(code: u16) -> Option<&'static str> {
+	match code {
+		0x0000 => Some("Head flying hours"),
+		_ => None,
+	}
+}
+
+fn decode(page: &log_page::Page) -> Option<Vec<log_page::DecodedParameter>> {
+	page.decode_with(param_name)
+}
+
+...
+
+let mut pages = SCSIPages::new(&dev);
+pages.register_vendor_page(VendorPage {
+	vendor: "ACME",
+	product: "",
+	page: 0x3c,
+	subpage: 0x00,
+	name: "Flight Recorder",
+	decode: decode,
+});
+
+let page = pages.get_page(0x3c, 0x00).unwrap();
+print!("{:?}\n", pages.decode_page(&page).unwrap());
+```
+*/
+
+use byteorder::{ReadBytesExt, BigEndian};
+
+use scsi::data::log_page::{Page, DecodedParameter, Value};
+
+/// Decoder for a vendor-specific log page
+#[derive(Debug, Clone)]
+pub struct VendorPage {
+	/// INQUIRY vendor identification (e.g. `SEAGATE`) of the devices this page applies to
+	pub vendor: &'static str,
+	/// Prefix of INQUIRY product identification of the devices this page applies to; empty string matches any product
+	pub product: &'static str,
+	pub page: u8,
+	pub subpage: u8,
+	pub name: &'static str,
+	/// Interprets the page; should return `None` if page data cannot be interpreted
+	pub decode: fn(&Page) -> Option<Vec<DecodedParameter>>,
+}
+
+impl VendorPage {
+	/// Tells whether this decoder applies to a device with given INQUIRY vendor and product identification.
+	pub fn matches(&self, vendor: &str, product: &str) -> bool {
+		self.vendor == vendor && product.starts_with(self.product)
+	}
+}
+
+fn seagate_cache_param(code: u16) -> Option<&'static str> {
+	match code {
+		0x0000 => Some("Blocks sent to initiator"),
+		0x0001 => Some("Blocks received from initiator"),
+		0x0002 => Some("Blocks read from cache and sent to initiator"),
+		0x0003 => Some("Read and write commands with size <= segment size"),
+		0x0004 => Some("Read and write commands with size > segment size"),
+		_ => None,
+	}
+}
+
+fn seagate_factory_param(code: u16) -> Option<&'static str> {
+	match code {
+		0x0000 => Some("Power-on time, minutes"),
+		0x0008 => Some("Minutes until next internal S.M.A.R.T. test"),
+		_ => None,
+	}
+}
+
+fn seagate_cache(page: &Page) -> Option<Vec<DecodedParameter>> {
+	page.decode_with(seagate_cache_param)
+}
+
+fn seagate_factory(page: &Page) -> Option<Vec<DecodedParameter>> {
+	page.decode_with(seagate_factory_param)
+}
+
+// parameters of these pages are not documented publicly, so these only provide page names
+fn unnamed_params(page: &Page) -> Option<Vec<DecodedParameter>> {
+	page.decode_with(|_| None)
+}
+
+/**
+HGST/WDC Miscellaneous page packs everything into a single parameter 0000h; this splits it into separate values (all sharing parameter code 0000h).
+
+Field layout is the same as the one sg_logs(8) uses.
+*/
+fn hgst_misc(page: &Page) -> Option<Vec<DecodedParameter>> {
+	let params = match page.parse_params() {
+		Some(params) => params,
+		None => return None,
+	};
+
+	let mut out = vec![];
+	for param in params {
+		let v = &param.value;
+		if param.code != 0x0000 || v.len() < 44 {
+			out.push(DecodedParameter {
+				code: param.code,
+				name: None,
+				threshold_comparison: param.threshold_comparison,
+				value: param.decode_value(),
+			});
+			continue;
+		}
+
+		let fields: [(&'static str, u64); 11] = [
+			("Power-on hours", (&v[0..4]).read_u32::<BigEndian>().unwrap() as u64),
+			("Total bytes read", (&v[4..12]).read_u64::<BigEndian>().unwrap()),
+			("Total bytes written", (&v[12..20]).read_u64::<BigEndian>().unwrap()),
+			("Max drive temperature, °C", v[20] as u64),
+			("GList size", (&v[21..23]).read_u16::<BigEndian>().unwrap() as u64),
+			("Number of informational exceptions", v[23] as u64),
+			("Media exception", (v[24] >> 7) as u64),
+			("Hardware exception", ((v[24] >> 6) & 1) as u64),
+			("Total read commands", (&v[25..33]).read_u64::<BigEndian>().unwrap()),
+			("Total write commands", (&v[33..41]).read_u64::<BigEndian>().unwrap()),
+			("Flash correction count", (&v[42..44]).read_u16::<BigEndian>().unwrap() as u64),
+		];
+		for &(name, value) in fields.iter() {
+			out.push(DecodedParameter {
+				code: param.code,
+				name: Some(name),
+				threshold_comparison: param.threshold_comparison,
+				value: Value::Counter(value),
+			});
+		}
+	}
+
+	Some(out)
+}
+
+/// Returns the list of decoders that this crate ships with.
+pub fn builtin() -> Vec<VendorPage> {
+	let page = |vendor: &'static str, page: u8, name: &'static str, decode: fn(&Page) -> Option<Vec<DecodedParameter>>| VendorPage {
+		vendor: vendor,
+		product: "",
+		page: page,
+		subpage: 0x00,
+		name: name,
+		decode: decode,
+	};
+
+	vec![
+		page("SEAGATE", 0x37, "Cache Statistics", seagate_cache),
+		page("SEAGATE", 0x3e, "Factory Log", seagate_factory),
+
+		page("HGST", 0x30, "Performance Counters", unnamed_params),
+		page("HGST", 0x37, "Miscellaneous", hgst_misc),
+		page("HITACHI", 0x30, "Performance Counters", unnamed_params),
+		page("HITACHI", 0x37, "Miscellaneous", hgst_misc),
+		page("WDC", 0x30, "Performance Counters", unnamed_params),
+		page("WDC", 0x37, "Miscellaneous", hgst_misc),
+
+		page("TOSHIBA", 0x37, "Device Statistics", unnamed_params),
+	]
+}
@@ -0,0 +1,71 @@
+/*!
+Functions to parse and structs to represent ATA Information VPD page (89h).
+
+This page is only returned by SCSI to ATA Translation (SAT) layers, and describes both the SATL itself and the ATA device behind it.
+
+See SAT-3, 12.4.2 ATA Information VPD page.
+*/
+
+use ata::RegistersRead;
+use ata::data::id;
+use scsi::data::ascii;
+use super::page_data;
+
+#[derive(Debug)]
+pub struct ATAInformation {
+	/// Vendor of the SCSI to ATA Translation layer (e.g. `linux` for libata, or HBA vendor)
+	pub sat_vendor_id: String,
+	pub sat_product_id: String,
+	pub sat_product_rev: String,
+	/// ATA registers as they were after the device reset (taken from the Register Device-to-Host FIS for SATA devices)
+	pub signature: RegistersRead,
+	/// Command that was used to obtain the `identify` data: ECh (IDENTIFY DEVICE), A1h (IDENTIFY PACKET DEVICE), or 00h if there's no data
+	pub command: u8,
+	/// IDENTIFY DEVICE or IDENTIFY PACKET DEVICE data, exactly as returned by the ATA device
+	pub identify: Vec<u8>,
+}
+
+impl ATAInformation {
+	/// Parses IDENTIFY DEVICE data; returns `None` if device did not return any, or if it is a packet (ATAPI) device.
+	pub fn id(&self) -> Option<id::Id> {
+		if self.command != 0xec || self.identify.len() < 512 { return None; }
+		Some(id::parse_id(&self.identify))
+	}
+
+	/// Tells whether device signature is that of a packet (ATAPI) device.
+	pub fn is_packet_device(&self) -> bool {
+		self.signature.cyl_low == 0x14 && self.signature.cyl_high == 0xeb
+	}
+}
+
+/// Parses ATA Information VPD page; returns `None` if page is malformed.
+pub fn parse(data: &[u8]) -> Option<ATAInformation> {
+	let data = match page_data(data, 0x89) {
+		Some(data) => data,
+		None => return None,
+	};
+	// up to the COMMAND CODE field (data[4..] in terms of the full page)
+	if data.len() < 56 { return None; }
+
+	// signature is a Register Device-to-Host FIS for SATA devices; see SATA 3.2, 10.5.6
+	let sig = &data[32..52];
+
+	Some(ATAInformation {
+		sat_vendor_id: ascii(&data[4..12]),
+		sat_product_id: ascii(&data[12..28]),
+		sat_product_rev: ascii(&data[28..32]),
+		signature: RegistersRead {
+			status: sig[2],
+			error: sig[3],
+			sector: sig[4],
+			cyl_low: sig[5],
+			cyl_high: sig[6],
+			device: sig[7],
+			sector_count: sig[12],
+		},
+		command: data[52],
+		identify: if data.len() >= 56 + 512 {
+			data[56 .. 56 + 512].to_vec()
+		} else { vec![] },
+	})
+}
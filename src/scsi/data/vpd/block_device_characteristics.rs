@@ -0,0 +1,119 @@
+/*!
+Functions to parse and structs to represent Block Device Characteristics VPD page (B1h).
+
+See SBC-4, 6.6.2 Block Device Characteristics VPD page.
+*/
+
+use byteorder::{ReadBytesExt, BigEndian};
+use ata::data::id::RPM;
+use super::page_data;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serializable", derive(Serialize))]
+pub enum ProductType {
+	NotIndicated,
+	CFast,
+	CompactFlash,
+	MemoryStick,
+	MultiMediaCard,
+	SecureDigital,
+	XQD,
+	UniversalFlashStorage,
+	Reserved(u8),
+	VendorSpecific(u8),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serializable", derive(Serialize))]
+pub enum FormFactor {
+	NotReported,
+	/// 5.25 inch
+	Inch5_25,
+	/// 3.5 inch
+	Inch3_5,
+	/// 2.5 inch
+	Inch2_5,
+	/// 1.8 inch
+	Inch1_8,
+	/// Less than 1.8 inch
+	LessThan1_8,
+	Reserved(u8),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serializable", derive(Serialize))]
+pub enum Zoned {
+	/// Device is not zoned, or is host managed (see peripheral device type in standard INQUIRY data)
+	NotReported,
+	HostAware,
+	DeviceManaged,
+	Reserved,
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serializable", derive(Serialize))]
+pub struct BlockDeviceCharacteristics {
+	pub rpm: RPM,
+	pub product_type: ProductType,
+	/// Write After Block Erase REQuired (WABEREQ)
+	pub wabereq: u8,
+	/// Write After Cryptographic Erase REQuired (WACEREQ)
+	pub wacereq: u8,
+	pub form_factor: FormFactor,
+	pub zoned: Zoned,
+	/// FORCE UNIT ACCESS bit in the SYNCHRONIZE CACHE command behaves as described in SBC-4 (FUAB)
+	pub fuab: bool,
+	/// Verify Byte Check Unmapped LBA Supported (VBULS)
+	pub vbuls: bool,
+}
+
+/// Parses Block Device Characteristics VPD page; returns `None` if page is malformed.
+pub fn parse(data: &[u8]) -> Option<BlockDeviceCharacteristics> {
+	let data = match page_data(data, 0xb1) {
+		Some(data) => data,
+		None => return None,
+	};
+	if data.len() < 5 { return None; }
+
+	Some(BlockDeviceCharacteristics {
+		rpm: RPM::from((&data[0..2]).read_u16::<BigEndian>().unwrap()),
+		product_type: {
+			use self::ProductType::*;
+			match data[2] {
+				0x00 => NotIndicated,
+				0x01 => CFast,
+				0x02 => CompactFlash,
+				0x03 => MemoryStick,
+				0x04 => MultiMediaCard,
+				0x05 => SecureDigital,
+				0x06 => XQD,
+				0x07 => UniversalFlashStorage,
+				x @ 0xf0 ... 0xff => VendorSpecific(x),
+				x => Reserved(x),
+			}
+		},
+		wabereq: data[3] >> 6,
+		wacereq: (data[3] >> 4) & 0b11,
+		form_factor: {
+			use self::FormFactor::*;
+			match data[3] & 0b1111 {
+				0x0 => NotReported,
+				0x1 => Inch5_25,
+				0x2 => Inch3_5,
+				0x3 => Inch2_5,
+				0x4 => Inch1_8,
+				0x5 => LessThan1_8,
+				x => Reserved(x),
+			}
+		},
+		zoned: match (data[4] >> 4) & 0b11 {
+			0b00 => Zoned::NotReported,
+			0b01 => Zoned::HostAware,
+			0b10 => Zoned::DeviceManaged,
+			0b11 => Zoned::Reserved,
+			_ => unreachable!(),
+		},
+		fuab: data[4] & 0b10 != 0,
+		vbuls: data[4] & 0b1 != 0,
+	})
+}
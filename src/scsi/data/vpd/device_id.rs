@@ -1,24 +1,60 @@
-#[derive(Debug)]
+/*!
+Functions to parse and structs to represent Device Identification VPD page (83h).
+
+See SPC-5, 7.7.6 Device Identification VPD page.
+
+## Example
+
+```ignore
+use hdd::scsi::SCSICommon;
+use hdd::scsi::data::vpd::device_id;
+
+...
+
+let descriptors = dev.device_identification().unwrap();
+for d in &descriptors {
+	print!("{:?} {:?}: {:?}\n", d.assoc, d.proto, d.id.canonical());
+}
+
+if let Some(id) = device_id::logical_unit_id(&descriptors) {
+	print!("LU: {}\n", id.canonical().unwrap());
+}
+```
+*/
+
+use byteorder::{ReadBytesExt, BigEndian};
+use super::page_data;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serializable", derive(Serialize))]
 pub enum Protocol {
 	None,
-	FC, // Fibre Channel, FCP-2
-	SCSI, // Parallel SCSI, SPI-4
+	FC, // Fibre Channel, FCP-4
+	SCSI, // Parallel SCSI, SPI-5 (obsolete)
 	SSA, // SSA-S3P
-	FireWire, // IEEE 1394, SBP-2
-	RDMA, // SRP
+	FireWire, // IEEE 1394, SBP-3
+	RDMA, // SRP-2
 	ISCSI,
 	SAS,
+	ADT, // Automation/Drive Interface Transport, ADT-2
+	ATA, // AT Attachment Interface, ACS-2
+	UAS, // USB Attached SCSI, UAS-2
+	SOP, // SCSI over PCI Express, SOP
+	PCIe, // PCI Express
 	Reserved(u8),
 }
 
-#[derive(PartialEq, Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serializable", derive(Serialize))]
 pub enum CodeSet {
 	Binary,
 	ASCII, // 0x20 through 0x7e
+	UTF8,
 	Reserved(u8),
 }
 
-#[derive(PartialEq, Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serializable", derive(Serialize))]
 pub enum Association {
 	Device, // addressed physical or logical device
 	Port, // port that received the request
@@ -26,62 +62,197 @@ pub enum Association {
 	Reserved,
 }
 
-#[derive(Debug)]
-pub enum Identifier<'a> {
-	VendorSpecific(&'a [u8]),
-	// TODO? [u8; 8] for vendor_id
-	Generic { vendor_id: &'a [u8], id: &'a [u8] },
-	FCNameIdentifier(&'a [u8]), // FC-PH/FC-PH3/FC-FS Name_Identifier
-	// TODO? [u8; 8]
-	EUI64(&'a [u8]), // IEEE Extended Unique Identifier
-	Port(u32),
-	MD5(&'a [u8]),
+/// Network Address Authority designator; see SPC-5, 7.7.6.6 NAA designator format
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serializable", derive(Serialize))]
+pub enum NAA {
+	/// NAA 2h
+	IEEEExtended { vendor_specific_a: u16, company_id: u32, vendor_specific_b: u32 },
+	/// NAA 3h; 60-bit value
+	LocallyAssigned(u64),
+	/// NAA 5h
+	IEEERegistered { company_id: u32, vendor_specific: u64 },
+	/// NAA 6h
+	IEEERegisteredExtended { company_id: u32, vendor_specific: u64, extension: u64 },
+	/// Unknown NAA type, or designator length that does not match NAA type
+	Reserved(Vec<u8>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serializable", derive(Serialize))]
+pub enum Identifier {
+	VendorSpecific(Vec<u8>),
+	/// T10 vendor ID based designator
+	T10 { vendor_id: String, id: Vec<u8> },
+	/// IEEE Extended Unique Identifier, 8, 12 or 16 bytes long
+	EUI64(Vec<u8>),
+	NAA(NAA),
+	RelativeTargetPort(u16),
+	TargetPortGroup(u16),
+	LogicalUnitGroup(u16),
+	MD5(Vec<u8>),
+	/// SCSI name string, e.g. `iqn.…`, `eui.…` or `naa.…`
+	SCSIName(String),
+	ProtocolSpecificPort(Vec<u8>),
+	/// RFC 4122 UUID
+	UUID(Vec<u8>),
 	Reserved(u8),
+	/// Designator does not have the length or code set it is required to have
 	Invalid,
 }
 
-#[derive(Debug)]
-pub struct Descriptor<'a> {
+fn to_hex(data: &[u8]) -> String {
+	data.iter().map(|x| format!("{:02x}", x)).collect()
+}
+
+impl Identifier {
+	/**
+	Returns canonical string form of the identifier, as it appears in SCSI name strings (`naa.5000c500…`, `eui.…`) or in `t10.` form.
+
+	Returns `None` for identifiers that have no such form (e.g. relative port numbers or vendor-specific data).
+	*/
+	pub fn canonical(&self) -> Option<String> {
+		use self::Identifier::*;
+		match *self {
+			NAA(ref naa) => Some(format!("naa.{}", match *naa {
+				self::NAA::IEEEExtended { vendor_specific_a, company_id, vendor_specific_b } =>
+					format!("2{:03x}{:06x}{:06x}", vendor_specific_a, company_id, vendor_specific_b),
+				self::NAA::LocallyAssigned(value) =>
+					format!("3{:015x}", value),
+				self::NAA::IEEERegistered { company_id, vendor_specific } =>
+					format!("5{:06x}{:09x}", company_id, vendor_specific),
+				self::NAA::IEEERegisteredExtended { company_id, vendor_specific, extension } =>
+					format!("6{:06x}{:09x}{:016x}", company_id, vendor_specific, extension),
+				self::NAA::Reserved(ref data) => to_hex(data),
+			})),
+			EUI64(ref id) => Some(format!("eui.{}", to_hex(id))),
+			T10 { ref vendor_id, ref id } => Some(format!("t10.{:8}{}", vendor_id, String::from_utf8_lossy(id).trim())),
+			SCSIName(ref name) => Some(name.clone()),
+			UUID(ref id) => Some(format!("uuid.{}-{}-{}-{}-{}", to_hex(&id[0..4]), to_hex(&id[4..6]), to_hex(&id[6..8]), to_hex(&id[8..10]), to_hex(&id[10..16]))),
+			VendorSpecific(_) | RelativeTargetPort(_) | TargetPortGroup(_) | LogicalUnitGroup(_) | MD5(_) | ProtocolSpecificPort(_) | Reserved(_) | Invalid => None,
+		}
+	}
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serializable", derive(Serialize))]
+pub struct Descriptor {
 	pub proto: Protocol,
 	pub codeset: CodeSet,
 	pub assoc: Association,
-	pub id: Identifier<'a>,
+	pub id: Identifier,
+}
+
+fn parse_naa(id: &[u8]) -> NAA {
+	let n = |data: &[u8]| (&data[..]).read_u64::<BigEndian>().unwrap();
+
+	match (id[0] >> 4, id.len()) {
+		(0x2, 8) => NAA::IEEEExtended {
+			vendor_specific_a: (&id[0..2]).read_u16::<BigEndian>().unwrap() & 0x0fff,
+			company_id: ((id[2] as u32) << 16) + ((id[3] as u32) << 8) + (id[4] as u32),
+			vendor_specific_b: ((id[5] as u32) << 16) + ((id[6] as u32) << 8) + (id[7] as u32),
+		},
+		(0x3, 8) => NAA::LocallyAssigned(n(id) & 0x0fff_ffff_ffff_ffff),
+		(0x5, 8) => NAA::IEEERegistered {
+			company_id: ((n(id) >> 36) & 0xff_ffff) as u32,
+			vendor_specific: n(id) & 0xf_ffff_ffff,
+		},
+		(0x6, 16) => NAA::IEEERegisteredExtended {
+			company_id: ((n(id) >> 36) & 0xff_ffff) as u32,
+			vendor_specific: n(id) & 0xf_ffff_ffff,
+			extension: n(&id[8..16]),
+		},
+		_ => NAA::Reserved(id.to_vec()),
+	}
+}
+
+fn port_number(id: &[u8], codeset: CodeSet) -> Option<u16> {
+	if codeset == CodeSet::Binary && id.len() == 4 {
+		Some((&id[2..4]).read_u16::<BigEndian>().unwrap())
+	} else { None }
 }
 
-pub fn parse(data: &[u8]) -> Vec<Descriptor> {
+fn parse_identifier(kind: u8, codeset: CodeSet, assoc: Association, id: &[u8]) -> Identifier {
+	use self::Identifier::*;
+	match kind {
+		0x0 => VendorSpecific(id.to_vec()),
+		0x1 => if id.len() < 8 { Invalid } else {
+			T10 {
+				vendor_id: String::from_utf8_lossy(&id[0..8]).trim().to_string(),
+				id: id[8..].to_vec(),
+			}
+		},
+		0x2 => match id.len() {
+			8 | 12 | 16 => EUI64(id.to_vec()),
+			_ => Invalid,
+		},
+		0x3 => if id.is_empty() { Invalid } else { NAA(parse_naa(id)) },
+		0x4 if assoc == Association::Port => port_number(id, codeset).map(RelativeTargetPort).unwrap_or(Invalid),
+		0x5 if assoc == Association::Port => port_number(id, codeset).map(TargetPortGroup).unwrap_or(Invalid),
+		0x6 if assoc == Association::Device => port_number(id, codeset).map(LogicalUnitGroup).unwrap_or(Invalid),
+		0x7 => MD5(id.to_vec()),
+		0x8 => if codeset != CodeSet::UTF8 { Invalid } else {
+			// null-terminated, and might be padded with more nulls
+			let len = id.iter().position(|&x| x == 0).unwrap_or(id.len());
+			SCSIName(String::from_utf8_lossy(&id[..len]).to_string())
+		},
+		0x9 if assoc == Association::Port => ProtocolSpecificPort(id.to_vec()),
+		0xa => if id.len() != 18 || id[0] >> 4 != 1 { Invalid } else { UUID(id[2..18].to_vec()) },
+		x => Reserved(x),
+	}
+}
+
+/**
+Parses Device Identification VPD page; returns `None` if page is malformed.
+
+Truncated descriptor (if any) is ignored.
+*/
+pub fn parse(data: &[u8]) -> Option<Vec<Descriptor>> {
+	let data = match page_data(data, 0x83) {
+		Some(data) => data,
+		None => return None,
+	};
+
 	let mut descriptors = vec![];
 
 	let mut i = 0;
-	while i < data.len() {
+	while i + 4 <= data.len() {
 		let idlen = data[i+3] as usize;
-		let id = &data[i .. i + idlen + 4];
+		if i + 4 + idlen > data.len() { break; }
+		let header = &data[i .. i + 4];
 
 		let proto = {
 			use self::Protocol::*;
-			if id[1] & 0b1000_0000 == 0 {
+			if header[1] & 0b1000_0000 == 0 {
 				None // Protocol Identifier Valid bit is not set, Protocol Identifier must be ignored
 			} else {
-				match id[0] >> 4 {
-					0 => FC,
-					1 => SCSI,
-					2 => SSA,
-					3 => FireWire,
-					4 => RDMA,
-					5 => ISCSI,
-					6 => SAS,
+				match header[0] >> 4 {
+					0x0 => FC,
+					0x1 => SCSI,
+					0x2 => SSA,
+					0x3 => FireWire,
+					0x4 => RDMA,
+					0x5 => ISCSI,
+					0x6 => SAS,
+					0x7 => ADT,
+					0x8 => ATA,
+					0x9 => UAS,
+					0xa => SOP,
+					0xb => PCIe,
 					x => Reserved(x),
 				}
 			}
 		};
 
-		let codeset = match id[0] & 0b1111 {
+		let codeset = match header[0] & 0b1111 {
 			// 0 is also reserved
 			1 => CodeSet::Binary,
 			2 => CodeSet::ASCII,
+			3 => CodeSet::UTF8,
 			x => CodeSet::Reserved(x),
 		};
 
-		let assoc = match (id[1] >> 4) & 0b11 {
+		let assoc = match (header[1] >> 4) & 0b11 {
 			0 => Association::Device,
 			1 => Association::Port,
 			2 => Association::Target,
@@ -89,53 +260,43 @@ pub fn parse(data: &[u8]) -> Vec<Descriptor> {
 			_ => unreachable!(),
 		};
 
-		use self::Identifier::*;
-		let id = match id[1] & 0b1111 { // match by identifier type
-			0 => VendorSpecific(&id[4..]),
-			1 => Generic {
-				vendor_id: &id[4..12],
-				id: &id[12..],
-			},
-			2 => EUI64(&id[4..]),
-			3 => FCNameIdentifier(&id[4..]),
-			x@4 | x@5 => if assoc == Association::Port {
-				if !(codeset == CodeSet::Binary && idlen == 4) { Invalid }
-				else {
-					Port(
-						((id[4] as u32) << 24) +
-						((id[5] as u32) << 16) +
-						((id[6] as u32) << 8) +
-						((id[7] as u32))
-					)
-				}
-			} else {
-				Reserved(x)
-			},
-			6 => if assoc == Association::Device {
-				if !(codeset == CodeSet::Binary && idlen == 4) { Invalid }
-				else {
-					Port(
-						((id[4] as u32) << 24) +
-						((id[5] as u32) << 16) +
-						((id[6] as u32) << 8) +
-						((id[7] as u32))
-					)
-				}
-			} else {
-				Reserved(6)
-			},
-			7 => MD5(&id[4..]),
-			x => Reserved(x),
-		};
-
 		descriptors.push(Descriptor {
 			proto: proto,
 			codeset: codeset,
 			assoc: assoc,
-			id: id,
+			id: parse_identifier(header[1] & 0b1111, codeset, assoc, &data[i + 4 .. i + 4 + idlen]),
 		});
 
 		i += 4 + idlen;
 	}
-	descriptors
+
+	Some(descriptors)
+}
+
+/**
+Picks the identifier of the addressed logical unit, the one to tell this logical unit apart from any other (e.g. to find the same disk behind several paths or HBAs).
+
+IEEE-based NAA designators are preferred (IEEE Registered Extended, IEEE Registered, IEEE Extended), then EUI-64, SCSI name string, UUID, and locally assigned NAA designator; T10 vendor ID based designator is the last resort, as it is not guaranteed to be globally unique.
+*/
+pub fn logical_unit_id(descriptors: &[Descriptor]) -> Option<&Identifier> {
+	let rank = |id: &Identifier| {
+		use self::Identifier::*;
+		match *id {
+			NAA(self::NAA::IEEERegisteredExtended {..}) => Some(0),
+			NAA(self::NAA::IEEERegistered {..}) => Some(1),
+			NAA(self::NAA::IEEEExtended {..}) => Some(2),
+			EUI64(_) => Some(3),
+			SCSIName(_) => Some(4),
+			UUID(_) => Some(5),
+			NAA(self::NAA::LocallyAssigned(_)) => Some(6),
+			T10 {..} => Some(7),
+			_ => None,
+		}
+	};
+
+	descriptors.iter()
+		.filter(|d| d.assoc == Association::Device)
+		.filter_map(|d| rank(&d.id).map(|rank| (rank, &d.id)))
+		.min_by_key(|&(rank, _)| rank)
+		.map(|(_, id)| id)
 }
@@ -0,0 +1,127 @@
+/*!
+Functions to parse and structs to represent Extended INQUIRY Data VPD page (86h).
+
+See SPC-4, 7.8.7 Extended INQUIRY Data VPD page.
+*/
+
+use byteorder::{ReadBytesExt, BigEndian};
+use super::page_data;
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serializable", derive(Serialize))]
+pub struct ExtendedInquiry {
+	/// When device activates downloaded microcode; see ACTIVATE MICROCODE field
+	pub activate_microcode: u8,
+	/// Protection types (1, 2 and/or 3) that device supports; only meaningful if PROTECT bit is set in standard INQUIRY data
+	pub protection_types: Vec<u8>,
+	/// Device checks the logical block guard field
+	pub guard_check: bool,
+	/// Device checks the logical block application tag field
+	pub application_tag_check: bool,
+	/// Device checks the logical block reference tag field
+	pub reference_tag_check: bool,
+
+	/// Device supports UNIT ATTENTION condition sense key specific data
+	pub ua_sense_key_specific: bool,
+	pub grouping_function: bool,
+	pub command_priority: bool,
+	/// Device supports HEAD OF QUEUE task attribute (HEADSUP)
+	pub head_of_queue: bool,
+	/// Device supports ORDERED task attribute (ORDSUP)
+	pub ordered: bool,
+	/// Device supports SIMPLE task attribute (SIMPSUP)
+	pub simple: bool,
+
+	/// Device supports WRITE UNCORRECTABLE with WR_UNCOR bit set (WU_SUP)
+	pub write_uncorrectable: bool,
+	/// Device supports WRITE UNCORRECTABLE with COR_DIS bit set (CRD_SUP)
+	pub correction_disable: bool,
+	/// Device has non-volatile cache (NV_SUP)
+	pub nonvolatile_cache: bool,
+	/// Device has volatile cache (V_SUP)
+	pub volatile_cache: bool,
+
+	/// Protection information interval is supported
+	pub protection_interval: bool,
+	/// Device clears any unit attention condition with the REPORTED LUNS DATA HAS CHANGED additional sense code for all the I_T nexuses (LUICLR)
+	pub luns_changed_clear: bool,
+
+	/// Device supports referrals
+	pub referrals: bool,
+	/// Capability-based command security
+	pub capability_based_security: bool,
+
+	pub multi_it_nexus_microcode_download: u8,
+	/// Time (in minutes) that device requires to complete extended self-test
+	pub extended_self_test_minutes: u16,
+
+	/// Device supports POWER ON activation of the microcode (POA_SUP)
+	pub power_on_activation: bool,
+	/// Device supports HARD RESET activation of the microcode (HRA_SUP)
+	pub hard_reset_activation: bool,
+	/// Device supports vendor-specific activation of the microcode (VSA_SUP)
+	pub vendor_specific_activation: bool,
+
+	/// Maximum length, in bytes, of sense data that device is able to return; 0 if not reported
+	pub max_sense_length: u8,
+}
+
+fn is_set(x: u8, bit: usize) -> bool {
+	x & (1<<bit) != 0
+}
+
+/// Parses Extended INQUIRY Data VPD page; returns `None` if page is malformed.
+pub fn parse(data: &[u8]) -> Option<ExtendedInquiry> {
+	let data = match page_data(data, 0x86) {
+		Some(data) => data,
+		None => return None,
+	};
+	// page length is 3Ch, although earlier revisions of this page are shorter
+	if data.len() < 8 { return None; }
+	// fields that did not make it into shorter page revisions are considered reserved, i.e. zero
+	let byte = |i: usize| data.get(i).cloned().unwrap_or(0);
+
+	Some(ExtendedInquiry {
+		activate_microcode: byte(0) >> 6,
+		protection_types: match (byte(0) >> 3) & 0b111 { // SPT
+			0b000 => vec![1],
+			0b001 => vec![1, 2],
+			0b010 => vec![2],
+			0b011 => vec![1, 3],
+			0b100 => vec![3],
+			0b101 => vec![2, 3],
+			0b111 => vec![1, 2, 3],
+			_ => vec![], // reserved
+		},
+		guard_check: is_set(byte(0), 2),
+		application_tag_check: is_set(byte(0), 1),
+		reference_tag_check: is_set(byte(0), 0),
+
+		ua_sense_key_specific: is_set(byte(1), 5),
+		grouping_function: is_set(byte(1), 4),
+		command_priority: is_set(byte(1), 3),
+		head_of_queue: is_set(byte(1), 2),
+		ordered: is_set(byte(1), 1),
+		simple: is_set(byte(1), 0),
+
+		write_uncorrectable: is_set(byte(2), 3),
+		correction_disable: is_set(byte(2), 2),
+		nonvolatile_cache: is_set(byte(2), 1),
+		volatile_cache: is_set(byte(2), 0),
+
+		protection_interval: is_set(byte(3), 4),
+		luns_changed_clear: is_set(byte(3), 0),
+
+		referrals: is_set(byte(4), 4),
+		capability_based_security: is_set(byte(4), 0),
+
+		multi_it_nexus_microcode_download: byte(5) & 0b1111,
+		extended_self_test_minutes: (&[byte(6), byte(7)][..]).read_u16::<BigEndian>().unwrap(),
+
+		power_on_activation: is_set(byte(8), 7),
+		hard_reset_activation: is_set(byte(8), 6),
+		vendor_specific_activation: is_set(byte(8), 5),
+
+		max_sense_length: byte(9),
+	})
+}
@@ -1 +1,35 @@
+/*!
+Functions to parse and structs to represent Vital Product Data (VPD) pages.
+
+See SPC-4, 7.8 Vital product data parameters.
+*/
+
 pub mod device_id;
+pub mod extended_inquiry;
+pub mod mode_page_policy;
+pub mod block_device_characteristics;
+pub mod ata_information;
+
+use super::ascii;
+
+/**
+Checks VPD page header and returns page contents without that header.
+
+Returns `None` if page code in the header does not match `page`, or if data is too short to contain the header. Page contents are truncated to the page length reported in the header.
+*/
+pub fn page_data(data: &[u8], page: u8) -> Option<&[u8]> {
+	if data.len() < 4 || data[1] != page { return None; }
+
+	let len = ((data[2] as usize) << 8) + (data[3] as usize);
+	Some(&data[4 .. ::std::cmp::min(data.len(), 4 + len)])
+}
+
+/// Parses Supported VPD Pages (00h), returning list of supported page codes.
+pub fn supported_pages(data: &[u8]) -> Option<Vec<u8>> {
+	page_data(data, 0x00).map(|pages| pages.to_vec())
+}
+
+/// Parses Unit Serial Number VPD page (80h).
+pub fn unit_serial_number(data: &[u8]) -> Option<String> {
+	page_data(data, 0x80).map(ascii)
+}
@@ -0,0 +1,55 @@
+/*!
+Functions to parse and structs to represent Mode Page Policy VPD page (87h).
+
+See SPC-4, 7.8.9 Mode Page Policy VPD page.
+*/
+
+use super::page_data;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serializable", derive(Serialize))]
+pub enum Policy {
+	/// Single copy of the mode page is shared by all I_T nexuses
+	Shared,
+	/// Separate copy of the mode page for each target port
+	PerTargetPort,
+	Obsolete,
+	/// Separate copy of the mode page for each I_T nexus
+	PerITNexus,
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serializable", derive(Serialize))]
+pub struct ModePagePolicy {
+	/// Policy page code; 3Fh with subpage FFh means all the mode pages and subpages not described by other descriptors
+	pub page: u8,
+	pub subpage: u8,
+	/// Multiple logical units share this mode page (MLUS)
+	pub shared_by_luns: bool,
+	pub policy: Policy,
+}
+
+/// Parses Mode Page Policy VPD page; returns `None` if page is malformed.
+pub fn parse(data: &[u8]) -> Option<Vec<ModePagePolicy>> {
+	let data = match page_data(data, 0x87) {
+		Some(data) => data,
+		None => return None,
+	};
+
+	Some(data.chunks(4)
+		.filter(|d| d.len() == 4)
+		.map(|d| ModePagePolicy {
+			page: d[0] & 0b0011_1111,
+			subpage: d[1],
+			shared_by_luns: d[2] & 0b1000_0000 != 0,
+			policy: match d[2] & 0b11 {
+				0b00 => Policy::Shared,
+				0b01 => Policy::PerTargetPort,
+				0b10 => Policy::Obsolete,
+				0b11 => Policy::PerITNexus,
+				_ => unreachable!(),
+			},
+		})
+		.collect()
+	)
+}
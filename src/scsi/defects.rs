@@ -0,0 +1,125 @@
+/*!
+Functions to query primary (factory) and grown defect lists
+
+## Example
+
+```ignore
+use hdd::Device;
+use hdd::scsi::SCSIDevice;
+use hdd::scsi::defects::{Defects, List};
+use hdd::scsi::data::defect::Format;
+
+...
+
+print!("Elements in grown defect list: {}\n", dev.defect_count(List::Grown).unwrap());
+
+for defect in dev.defect_list(List::Grown, Format::LongBlock).unwrap().defects {
+	print!("{:?}\n", defect);
+}
+```
+*/
+
+use scsi;
+use scsi::{SCSIDevice, SCSICommon};
+use scsi::data::defect::{self, DefectList, Format};
+
+quick_error! {
+	#[derive(Debug)]
+	pub enum Error {
+		NotSupported {}
+		SCSI(err: scsi::Error) {
+			from()
+			display("{}", err)
+		}
+		/// failed to parse defect data
+		InvalidData(what: &'static str) {
+			display("Unable to {}", what)
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum List {
+	/// Primary defect list (P-list), i.e. defects found by the manufacturer
+	Primary,
+	/// Grown defect list (G-list), i.e. defects found since the device left the factory
+	Grown,
+	Both,
+}
+
+impl List {
+	/// Returns tuple of `(plist, glist)` bits
+	fn bits(&self) -> (bool, bool) {
+		match *self {
+			List::Primary => (true, false),
+			List::Grown => (false, true),
+			List::Both => (true, true),
+		}
+	}
+}
+
+fn into_error(err: scsi::Error) -> Error {
+	match err {
+		ref err if err.is_invalid_field_in_cdb() => Error::NotSupported,
+		// DEFECT LIST NOT FOUND, PRIMARY DEFECT LIST NOT FOUND, GROWN DEFECT LIST NOT FOUND
+		scsi::Error::Sense(_, 0x1c, _) => Error::NotSupported,
+		err => Error::SCSI(err),
+	}
+}
+
+/// See [module documentation](index.html).
+pub trait Defects: SCSICommon {
+	/**
+	Issues READ DEFECT DATA for a list `list` in format `format`, and returns the defect list.
+
+	READ DEFECT DATA (12) is tried first; if device does not support it, READ DEFECT DATA (10) is used instead, which might only return part of the list if the list is long enough.
+	If `alloc` is the length of the header (8 bytes), only the number of defects is returned, without the list itself.
+	*/
+	fn read_defects(&self, list: List, format: Format, alloc: u32) -> Result<DefectList, Error> {
+		let (plist, glist) = list.bits();
+
+		let (sense, data) = self.read_defect_data_12(plist, glist, format.as_u8(), 0, alloc)?;
+		match scsi::check_sense(&sense) {
+			Ok(()) => defect::parse_12(&data).ok_or(Error::InvalidData("parse defect list")),
+			Err(ref err) if err.is_invalid_opcode() => {
+				info!("READ DEFECT DATA(12) is not supported, falling back to READ DEFECT DATA(10)");
+
+				let alloc = if alloc == 8 { 4 } else { ::std::cmp::min(alloc, 0xffff) as u16 };
+				let (sense, data) = self.read_defect_data_10(plist, glist, format.as_u8(), alloc)?;
+				scsi::check_sense(&sense).map_err(into_error)?;
+				defect::parse_10(&data).ok_or(Error::InvalidData("parse defect list"))
+			},
+			Err(err) => Err(into_error(err)),
+		}
+	}
+
+	/// Returns the number of entries in the defect list `list`.
+	fn defect_count(&self, list: List) -> Result<u32, Error> {
+		info!("querying defect count: {:?}", list);
+
+		// format does not really matter here, as long as device supports it and it is not vendor-specific
+		let defects = self.read_defects(list, Format::LongBlock, 8)?;
+		defects.count.ok_or(Error::InvalidData("count entries of the defect list"))
+	}
+
+	/**
+	Returns defect list `list`, with defects in format `format` (or in some other format, if device does not support this one).
+
+	Note that primary defect list might be quite long.
+	*/
+	fn defect_list(&self, list: List, format: Format) -> Result<DefectList, Error> {
+		info!("querying defect list: {:?} {:?}", list, format);
+
+		// ask for the header first to find out how long the list is
+		let header = self.read_defects(list, format, 8)?;
+		let len = match (header.count, header.format) {
+			(Some(count), Format::ShortBlock) => count.saturating_mul(4),
+			(Some(count), _) => count.saturating_mul(8),
+			(None, _) => return Ok(header),
+		};
+
+		self.read_defects(list, format, len.saturating_add(8))
+	}
+}
+
+impl Defects for SCSIDevice {}
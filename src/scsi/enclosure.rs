@@ -0,0 +1,199 @@
+/*!
+Functions to query and control SCSI enclosures (SES devices, i.e. devices of type 0Dh, or devices with `enclosure_services` bit set in INQUIRY response).
+
+See [`scsi::data::ses`](../data/ses/index.html) for the description of the pages.
+
+## Example
+
+```ignore
+use hdd::Device;
+use hdd::scsi::SCSIDevice;
+use hdd::scsi::enclosure::EnclosureServices;
+
+...
+
+for slot in dev.slots().unwrap() {
+	print!("{:?} {:?}: {:?}\n", slot.slot, slot.descriptor, slot.sas_addresses);
+}
+
+// light up identify LED of the first slot
+let slot = &dev.slots().unwrap()[0];
+dev.set_slot_leds(&slot.id, Some(true), None).unwrap();
+```
+*/
+
+use byteorder::{ReadBytesExt, BigEndian};
+
+use scsi;
+use scsi::{SCSIDevice, SCSICommon};
+use scsi::data::ses::{self, Configuration, ElementId, ElementType, ElementStatus, EnclosureStatus, AdditionalElementStatus, SlotControl};
+
+quick_error! {
+	#[derive(Debug)]
+	pub enum Error {
+		NotSupported {}
+		SCSI(err: scsi::Error) {
+			from()
+			display("{}", err)
+		}
+		/// failed to parse diagnostic page
+		InvalidData(what: &'static str) {
+			display("Unable to {}", what)
+		}
+	}
+}
+
+fn into_error(err: scsi::Error) -> Error {
+	if err.is_invalid_opcode() || err.is_invalid_field_in_cdb() {
+		Error::NotSupported
+	} else {
+		Error::SCSI(err)
+	}
+}
+
+/// Treats pages that enclosure does not support as empty.
+fn optional<T>(ret: Result<Vec<T>, Error>) -> Result<Vec<T>, Error> {
+	match ret {
+		Err(Error::NotSupported) => Ok(vec![]),
+		ret => ret,
+	}
+}
+
+/// Initial allocation length for diagnostic pages; commands are repeated with larger buffer if the page does not fit
+const ALLOC: u16 = 4096;
+
+/// Device slot (or array device slot) with everything enclosure knows about it
+#[derive(Debug)]
+#[cfg_attr(feature = "serializable", derive(Serialize))]
+pub struct Slot {
+	pub id: ElementId,
+	/// Slot number, as printed on the enclosure, if reported
+	pub slot: Option<u8>,
+	/// Element descriptor text, usually slot name
+	pub descriptor: Option<String>,
+	pub status: ElementStatus,
+	/// SAS addresses of the device in the slot (one per device port)
+	pub sas_addresses: Vec<u64>,
+}
+
+/// See [module documentation](index.html).
+pub trait EnclosureServices: SCSICommon {
+	/// Returns raw diagnostic page `page`.
+	fn diagnostic_page(&self, page: u8) -> Result<Vec<u8>, Error> {
+		info!("reading diagnostic page {:02x}", page);
+
+		let query = |alloc| -> Result<Vec<u8>, Error> {
+			let (sense, data) = self.receive_diagnostic_results(true, page, alloc)?;
+			scsi::check_sense(&sense).map_err(into_error)?;
+			Ok(data)
+		};
+
+		let data = query(ALLOC)?;
+		if data.len() < 4 {
+			return Err(Error::InvalidData("parse diagnostic page header"));
+		}
+
+		let len = (&data[2..4]).read_u16::<BigEndian>().unwrap() as u32 + 4;
+		if len > ALLOC as u32 {
+			// page is longer than the buffer
+			Ok(query(::std::cmp::min(len, 0xffff) as u16)?)
+		} else {
+			Ok(data)
+		}
+	}
+
+	/// Returns list of diagnostic pages that device supports.
+	fn supported_diagnostic_pages(&self) -> Result<Vec<u8>, Error> {
+		let data = self.diagnostic_page(0x00)?;
+		let len = (&data[2..4]).read_u16::<BigEndian>().unwrap() as usize;
+		Ok(data[4..].iter().take(len).cloned().collect())
+	}
+
+	fn ses_configuration(&self) -> Result<Configuration, Error> {
+		ses::parse_configuration(&self.diagnostic_page(0x01)?)
+			.ok_or(Error::InvalidData("parse Configuration diagnostic page"))
+	}
+
+	fn enclosure_status(&self, config: &Configuration) -> Result<EnclosureStatus, Error> {
+		ses::parse_enclosure_status(&self.diagnostic_page(0x02)?, config)
+			.ok_or(Error::InvalidData("parse Enclosure Status diagnostic page"))
+	}
+
+	/// Returns element descriptors (i.e. element names) in the same order as elements in [`Configuration::elements`](../data/ses/struct.Configuration.html#method.elements).
+	fn element_descriptors(&self, config: &Configuration) -> Result<Vec<(ElementId, String)>, Error> {
+		ses::parse_element_descriptors(&self.diagnostic_page(0x07)?, config)
+			.ok_or(Error::InvalidData("parse Element Descriptor diagnostic page"))
+	}
+
+	fn additional_element_status(&self, config: &Configuration) -> Result<Vec<AdditionalElementStatus>, Error> {
+		ses::parse_additional_element_status(&self.diagnostic_page(0x0a)?, config)
+			.ok_or(Error::InvalidData("parse Additional Element Status diagnostic page"))
+	}
+
+	/// Lists device slots of the enclosure, mapping slots to the SAS addresses of the devices in them. Element Descriptor and Additional Element Status pages are optional; if enclosure does not support either, corresponding fields are left empty.
+	fn slots(&self) -> Result<Vec<Slot>, Error> {
+		let config = self.ses_configuration()?;
+		let status = self.enclosure_status(&config)?;
+
+		let descriptors = optional(self.element_descriptors(&config))?;
+		let additional = optional(self.additional_element_status(&config))?;
+
+		Ok(status.elements.into_iter()
+			.filter(|e| e.id.index.is_some())
+			.filter(|e| match e.id.element_type {
+				ElementType::DeviceSlot | ElementType::ArrayDeviceSlot => true,
+				_ => false,
+			})
+			.map(|e| {
+				let descriptor = descriptors.iter()
+					.find(|d| d.0 == e.id)
+					.map(|d| d.1.clone());
+				let additional = additional.iter().find(|a| a.id == e.id);
+
+				Slot {
+					id: e.id,
+					slot: additional.and_then(|a| a.slot),
+					descriptor: descriptor,
+					sas_addresses: match additional {
+						Some(a) if !a.invalid => a.phys.iter()
+							.map(|phy| phy.sas_address)
+							.filter(|&addr| addr != 0)
+							.collect(),
+						_ => vec![],
+					},
+					status: e,
+				}
+			})
+			.collect()
+		)
+	}
+
+	/// Turns identify and fault LEDs of the device slot `id` on or off; `None` leaves corresponding LED as is. Other requests for this slot (e.g. DEVICE OFF) are kept intact.
+	fn set_slot_leds(&self, id: &ElementId, ident: Option<bool>, fault: Option<bool>) -> Result<(), Error> {
+		info!("setting slot LEDs: slot={:?} ident={:?} fault={:?}", id, ident, fault);
+
+		match id.element_type {
+			ElementType::DeviceSlot | ElementType::ArrayDeviceSlot => (),
+			_ => return Err(Error::NotSupported),
+		}
+
+		let config = self.ses_configuration()?;
+		let status = self.enclosure_status(&config)?;
+		let element = match status.elements.iter().find(|e| e.id == *id) {
+			Some(element) => element,
+			None => return Err(Error::InvalidData("find slot in Enclosure Status diagnostic page")),
+		};
+
+		let page = ses::compose_slot_control(&config, status.generation, element, SlotControl {
+			ident: ident,
+			fault: fault,
+		});
+
+		let sense = self.send_diagnostic(0, false, &page, scsi::DEFAULT_TIMEOUT)?;
+		scsi::check_sense(&sense).map_err(into_error)?;
+
+		Ok(())
+	}
+}
+
+impl EnclosureServices for SCSIDevice {}
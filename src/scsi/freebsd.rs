@@ -4,19 +4,16 @@ use self::libc::c_void;
 use cam::*;
 
 use Direction;
-use scsi::SCSIDevice;
+use scsi::{SCSIDevice, TransportError, PlatformResponse};
 
 use std::io;
 
 impl SCSIDevice {
-	/// Executes `cmd` and returns tuple of `(sense, data)`.
-	pub(crate) fn do_platform_cmd(&self, cmd: &[u8], dir: Direction, sense_len: usize, data_len: usize)-> Result<(Vec<u8>, Vec<u8>), io::Error> {
+	/// Executes `cmd` and returns tuple of `(status, transport error, sense, data)`. For `Direction::To`, `data` is what is sent to the device; otherwise it is a buffer to read data into. `timeout` is in seconds.
+	pub(crate) fn do_platform_cmd(&self, cmd: &[u8], dir: Direction, sense_len: usize, mut data: Vec<u8>, timeout: u32)-> Result<PlatformResponse, io::Error> {
 		// might've used Vec::with_capacity(), but this requires rebuilding with Vec::from_raw_parts() later on to hint actual size of data in buffer vecs,
 		// and we're not expecting this function to be someone's bottleneck
 		let mut sense = vec![0; sense_len];
-		let mut data = vec![0; data_len];
-
-		let timeout = 10; // in seconds; TODO configurable
 
 		let dev = &self.device.dev;
 
@@ -32,18 +29,18 @@ impl SCSIDevice {
 				use self::Direction::*;
 				use self::ccb_flags::*;
 				match dir {
-					// TODO &[u8] arg → data → csio.data_ptr for Direction::{To,Both}
+					// TODO Direction::Both
 					From => CAM_DIR_IN,
-					To => unimplemented!(), //CAM_DIR_OUT,
+					To => CAM_DIR_OUT,
 					Both => unimplemented!(), //CAM_DIR_BOTH,
 					None => CAM_DIR_NONE,
 				}
 			} as u32;
 			csio.ccb_h.xflags = 0;
 			csio.ccb_h.retry_count = 1;
-			csio.ccb_h.timeout = timeout*1000;
+			csio.ccb_h.timeout = timeout.saturating_mul(1000);
 			csio.data_ptr = data.as_mut_ptr();
-			csio.dxfer_len = data.capacity() as u32;
+			csio.dxfer_len = data.len() as u32;
 			csio.sense_len = sense.capacity() as u8;
 			csio.tag_action = MSG_SIMPLE_Q_TAG as u8;
 
@@ -59,11 +56,19 @@ impl SCSIDevice {
 		dev.send_ccb(&ccb)?;
 
 		let status = ccb.get_status();
-		if !(status == cam_status::CAM_REQ_CMP as u32 || status == cam_status::CAM_SCSI_STATUS_ERROR as u32) {
-			Err(error::from_status(dev, &ccb))?
-		}
+		let transport = {
+			use self::cam_status::*;
+			match status {
+				x if x == CAM_REQ_CMP as u32 || x == CAM_SCSI_STATUS_ERROR as u32 => None,
+				x if x == CAM_SCSI_BUS_RESET as u32 || x == CAM_BDR_SENT as u32 => Some(TransportError::Reset),
+				x if x == CAM_BUSY as u32 || x == CAM_SCSI_BUSY as u32 => Some(TransportError::BusBusy),
+				x if x == CAM_REQUEUE_REQ as u32 => Some(TransportError::SoftError),
+				x if x == CAM_CMD_TIMEOUT as u32 => Some(TransportError::Timeout),
+				_ => Err(error::from_status(dev, &ccb))?,
+			}
+		};
 
-		// TODO ccb.csio.scsi_status
+		let scsi_status = unsafe { ccb.csio().scsi_status };
 
 		let sense_len =
 			if (ccb.get_status_flags() & cam_status::CAM_AUTOSNS_VALID as u32) != 0 {
@@ -94,6 +99,8 @@ impl SCSIDevice {
 		};
 
 		Ok((
+			scsi_status,
+			transport,
 			sense[ .. sense_len as usize].to_vec(),
 			data[ .. data_len as usize].to_vec(),
 		))
@@ -11,7 +11,7 @@ use std::os::unix::io::AsRawFd;
 use std::io;
 
 use Direction;
-use scsi::SCSIDevice;
+use scsi::{SCSIDevice, TransportError, PlatformResponse};
 
 use std::cmp::max;
 
@@ -50,26 +50,41 @@ struct sg_io_hdr {
 	info:	c_uint,	// [o] auxiliary information
 }
 
+// see scsi/scsi.h, DID_* and DRIVER_* constants
+fn transport_error(host_status: c_ushort, driver_status: c_ushort) -> Option<TransportError> {
+	// upper bits of driver_status are suggestions (SUGGEST_RETRY &c), and DRIVER_SENSE merely says that sense buffer is filled
+	let driver = driver_status & 0x0f;
+	match (host_status, driver) {
+		(0x00, 0x00) | (0x00, 0x08) => None, // DID_OK; DRIVER_OK or DRIVER_SENSE
+		(0x08, _) => Some(TransportError::Reset), // DID_RESET
+		(0x02, _) | (_, 0x01) => Some(TransportError::BusBusy), // DID_BUS_BUSY; DRIVER_BUSY
+		(0x03, _) | (_, 0x06) => Some(TransportError::Timeout), // DID_TIME_OUT; DRIVER_TIMEOUT
+		// DID_SOFT_ERROR, DID_IMM_RETRY, DID_REQUEUE; DRIVER_SOFT
+		(0x0b, _) | (0x0c, _) | (0x0d, _) | (_, 0x02) => Some(TransportError::SoftError),
+		(host, driver) => Some(TransportError::Other(host, driver)),
+	}
+}
+
 impl SCSIDevice {
-	pub(crate) fn do_platform_cmd(&self, cmd: &[u8], dir: Direction, sense_len: usize, data_len: usize) -> Result<(Vec<u8>, Vec<u8>), io::Error> {
+	/// Executes `cmd` and returns tuple of `(status, transport error, sense, data)`. For `Direction::To`, `data` is what is sent to the device; otherwise it is a buffer to read data into. `timeout` is in seconds.
+	pub(crate) fn do_platform_cmd(&self, cmd: &[u8], dir: Direction, sense_len: usize, mut data: Vec<u8>, timeout: u32) -> Result<PlatformResponse, io::Error> {
 		// might've used Vec::with_capacity(), but this requires rebuilding with Vec::from_raw_parts() later on to hint actual size of data in buffer vecs,
 		// and we're not expecting this function to be someone's bottleneck
 		let mut sense = vec![0; sense_len];
-		let mut data = vec![0; data_len];
 
 		let hdr = sg_io_hdr {
 			interface_id:	'S' as c_int,
 
 			dxfer_direction: match dir {
 				// see scsi/sg.h, constants SG_DXFER_{NONE,{TO,FROM,TO_FROM}_DEV}
-				// TODO &[u8] arg → data → sg_io_hdr.dxferp for Direction::{To,Both}
+				// TODO Direction::Both
 				Direction::None => -1,
-				Direction::To => unimplemented!(), //-2,
+				Direction::To => -2,
 				Direction::From => -3,
 				Direction::Both => unimplemented!(), //-4,
 			},
 			dxferp:	data.as_mut_ptr() as *mut c_void,
-			dxfer_len:	data.capacity() as c_uint,
+			dxfer_len:	data.len() as c_uint,
 			resid:	0,
 
 			sbp:	sense.as_mut_ptr(),
@@ -83,7 +98,7 @@ impl SCSIDevice {
 			host_status:	0,
 			driver_status:	0,
 
-			timeout:	10000,	// TODO configurable
+			timeout:	timeout.saturating_mul(1000),
 			duration:	0,
 
 			iovec_count:	0,
@@ -108,6 +123,8 @@ impl SCSIDevice {
 		let data_len = hdr.dxfer_len - max(hdr.resid, 0) as u32;
 
 		Ok((
+			hdr.status,
+			transport_error(hdr.host_status, hdr.driver_status),
 			sense[ .. hdr.sb_len_wr as usize].to_vec(),
 			data[ .. data_len as usize].to_vec(),
 		))
@@ -0,0 +1,199 @@
+/*!
+Functions implementing typical SCSI commands: readiness checks, sense data retrieval and power condition control.
+
+## Example
+
+```ignore
+use hdd::Device;
+use hdd::scsi::SCSIDevice;
+use hdd::scsi::misc::{Misc, Readiness, PowerAction};
+
+...
+
+// e.g. after hot-insert
+match dev.wait_ready(Duration::from_secs(30)).unwrap() {
+	Readiness::Ready => println!("ready"),
+	Readiness::NeedsStart => dev.start_stop(PowerAction::Start, false).unwrap(),
+	x => println!("not ready: {:?}", x),
+}
+
+if let Some(sense) = dev.get_sense().unwrap() {
+	println!("power condition: {:?}", sense.power_condition());
+}
+
+// spin the disk down before pulling it out
+dev.start_stop(PowerAction::Stop, false).unwrap();
+```
+*/
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+use scsi;
+use scsi::{SCSIDevice, SCSICommon};
+use scsi::data::sense;
+
+quick_error! {
+	#[derive(Debug)]
+	pub enum Error {
+		SCSI(err: scsi::Error) {
+			from()
+			display("{}", err)
+		}
+	}
+}
+
+/// Interpreted result of TEST UNIT READY
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Readiness {
+	Ready,
+	/// Logical unit is in process of becoming ready (e.g. it is spinning up)
+	BecomingReady,
+	/// Logical unit needs START STOP UNIT to become ready (initializing command required)
+	NeedsStart,
+	/// Logical unit is busy with some long operation (e.g. format, self-test or sanitize), with its progress in percent if device reports it
+	InProgress { asc: u8, ascq: u8, progress: Option<f32> },
+	/// Logical unit is not ready for some other reason (e.g. no medium present); see `sense::key::decode_asc`
+	NotReady { asc: u8, ascq: u8 },
+}
+
+/// Action to perform with START STOP UNIT
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PowerAction {
+	/// Make logical unit ready (i.e. spin up the disk)
+	Start,
+	/// Stop the logical unit (i.e. spin down the disk)
+	Stop,
+	/// Load the medium
+	Load,
+	/// Eject the medium
+	Eject,
+	Active,
+	IdleA,
+	IdleB,
+	IdleC,
+	StandbyZ,
+	StandbyY,
+	/// Give control of power conditions back to the logical unit (i.e. to its power condition timers)
+	DeviceControl,
+}
+
+/// How many unit attention conditions to go through before giving up (these are queued, e.g. power on reset + mode parameters changed)
+const MAX_UNIT_ATTENTIONS: usize = 8;
+
+/// See [module documentation](index.html).
+pub trait Misc: SCSICommon {
+	/**
+	Issues TEST UNIT READY and interprets its result.
+
+	Pending unit attention conditions (e.g. reported after reset or hot-insert) are reported once and then cleared, so in that case the command is simply retried.
+	*/
+	fn readiness(&self) -> Result<Readiness, Error> {
+		for _ in 0 .. MAX_UNIT_ATTENTIONS {
+			let sense = self.test_unit_ready()?;
+			if sense.is_empty() { return Ok(Readiness::Ready); }
+
+			let sense = match sense::parse(&sense) {
+				Some((true, sense)) => sense,
+				// deferred errors are not related to this command
+				Some((false, _)) => return Ok(Readiness::Ready),
+				None => Err(scsi::Error::Nonsense)?,
+			};
+			let (key, asc, ascq) = sense.code();
+
+			match (key, asc, ascq) {
+				// no sense, recovered error, completed
+				(0x00, _, _) | (0x01, _, _) | (0x0f, _, _) => return Ok(Readiness::Ready),
+				(0x06, asc, ascq) => {
+					info!("unit attention: {}", sense::key::decode_asc(asc, ascq)
+						.map(|x| x.to_string())
+						.unwrap_or_else(|| format!("{:02x} {:02x}", asc, ascq)));
+					continue;
+				},
+				(0x02, 0x04, 0x01) => return Ok(Readiness::BecomingReady),
+				(0x02, 0x04, 0x02) => return Ok(Readiness::NeedsStart),
+				(0x02, 0x04, ascq) if sense.progress().is_some() => return Ok(Readiness::InProgress {
+					asc: 0x04,
+					ascq: ascq,
+					progress: sense.sense_key_specific().and_then(|sks| sks.percent()),
+				}),
+				(0x02, asc, ascq) => return Ok(Readiness::NotReady { asc: asc, ascq: ascq }),
+				(key, asc, ascq) => Err(scsi::Error::Sense(sense::key::SenseKey::from(key), asc, ascq))?,
+			}
+		}
+
+		// device keeps reporting unit attentions; let the caller decide what to do with that
+		let sense = self.test_unit_ready()?;
+		scsi::check_sense(&sense)?;
+		Ok(Readiness::Ready)
+	}
+
+	/**
+	Polls the device with TEST UNIT READY until it is ready, or until `timeout` expires, and returns the last readiness status.
+
+	This function does not try to spin up the device, so it also stops waiting once the device reports that it needs START STOP UNIT.
+	*/
+	fn wait_ready(&self, timeout: Duration) -> Result<Readiness, Error> {
+		info!("waiting for the device to become ready");
+
+		let start = Instant::now();
+		loop {
+			let status = self.readiness()?;
+			match status {
+				Readiness::Ready | Readiness::NeedsStart => return Ok(status),
+				_ if start.elapsed() >= timeout => return Ok(status),
+				_ => thread::sleep(Duration::from_secs(1)),
+			}
+		}
+	}
+
+	/**
+	Issues REQUEST SENSE and returns parsed sense data, or `None` if device returned none.
+
+	Use `Sense::progress` and `Sense::power_condition` to get progress of the current long operation and the power condition of the device.
+	*/
+	fn get_sense(&self) -> Result<Option<sense::Sense>, Error> {
+		let (sense, data) = self.request_sense(false)?;
+		scsi::check_sense(&sense)?;
+
+		if data.is_empty() {
+			return Ok(None);
+		}
+
+		match sense::parse(&data) {
+			Some((_, sense)) => Ok(Some(sense)),
+			None => Err(scsi::Error::Nonsense)?,
+		}
+	}
+
+	/**
+	Issues START STOP UNIT to perform `action`.
+
+	If `immediate` is set, command returns without waiting for the action to complete; use [wait_ready](#method.wait_ready) to wait for the device to spin up.
+	*/
+	fn start_stop(&self, action: PowerAction, immediate: bool) -> Result<(), Error> {
+		use self::PowerAction::*;
+
+		info!("performing power action {:?}", action);
+
+		// (power condition, modifier, loej, start)
+		let (pc, modifier, loej, start) = match action {
+			Start => (0x0, 0, false, true),
+			Stop => (0x0, 0, false, false),
+			Load => (0x0, 0, true, true),
+			Eject => (0x0, 0, true, false),
+			Active => (0x1, 0, false, false),
+			IdleA => (0x2, 0, false, false),
+			IdleB => (0x2, 1, false, false),
+			IdleC => (0x2, 2, false, false),
+			StandbyZ => (0x3, 0, false, false),
+			StandbyY => (0x3, 1, false, false),
+			DeviceControl => (0x7, 0, false, false),
+		};
+
+		let sense = self.start_stop_unit(immediate, pc, modifier, false, loej, start)?;
+		Ok(scsi::check_sense(&sense)?)
+	}
+}
+
+impl Misc for SCSIDevice {}
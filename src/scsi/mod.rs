@@ -8,7 +8,15 @@ All things SCSI.
 */
 
 pub mod data;
+pub mod misc;
+pub mod defects;
 pub mod pages;
+pub mod modes;
+pub mod self_test;
+pub mod retry;
+pub mod commands;
+pub mod enclosure;
+pub mod reservations;
 
 #[cfg(target_os = "linux")]
 mod linux;
@@ -17,20 +25,31 @@ mod linux;
 mod freebsd;
 
 use std::io;
+use std::collections::HashMap;
+use std::fmt;
+use std::error;
+use std::thread;
 use ata;
 use byteorder::{ReadBytesExt, BigEndian};
 use self::data::sense;
+use self::data::vpd;
+use self::data::vpd::device_id;
+use self::data::vpd::extended_inquiry::{self, ExtendedInquiry};
+use self::data::vpd::mode_page_policy::{self, ModePagePolicy};
+use self::data::vpd::block_device_characteristics::{self, BlockDeviceCharacteristics};
+use self::data::vpd::ata_information::{self, ATAInformation};
 
 use Direction;
 use Device;
 
 use utils::hexdump_8;
+use self::retry::RetryPolicy;
 
 quick_error! {
 	#[derive(Debug)]
 	pub enum Error {
+		// no from() here, see `impl From<io::Error>` below
 		IO(err: io::Error) {
-			from()
 			display("IO error: {}", err)
 			description(err.description())
 			cause(err)
@@ -46,9 +65,50 @@ quick_error! {
 					.map(|x| x.to_string())
 					.unwrap_or_else(|| format!("unknown additional sense code: {:02x} {:02x}", asc, ascq)))
 		}
-		// this is for Sense::Fixed(FixedData::Invalid(_))
+		// this is for sense of unknown format
 		// pun definitely intented at this point
 		Nonsense {}
+		/// command was not completed by the device, e.g. because it is busy or is reserved by another initiator
+		Status(status: Status) {
+			description("SCSI command not completed")
+			display("SCSI command not completed: {}", status)
+		}
+		/// device returned data that cannot be parsed
+		InvalidData(what: &'static str) {
+			display("Unable to {}", what)
+		}
+	}
+}
+
+impl From<io::Error> for Error {
+	/// Unwraps [`Status`](enum.Status.html) of the commands that were not completed (see [`SCSIDevice::do_cmd`](struct.SCSIDevice.html#method.do_cmd)), so callers can tell e.g. RESERVATION CONFLICT apart from the actual I/O errors.
+	fn from(err: io::Error) -> Self {
+		let status = match err.get_ref() {
+			Some(inner) if err.kind() == io::ErrorKind::Other => inner.downcast_ref::<Status>().cloned(),
+			_ => None,
+		};
+		match status {
+			Some(status) => Error::Status(status),
+			None => Error::IO(err),
+		}
+	}
+}
+
+impl Error {
+	/// Tells whether device rejected the command as unknown (Illegal Request / INVALID COMMAND OPERATION CODE).
+	pub fn is_invalid_opcode(&self) -> bool {
+		match *self {
+			Error::Sense(sense::key::SenseKey::IllegalRequest, 0x20, 0x00) => true,
+			_ => false,
+		}
+	}
+
+	/// Tells whether device rejected some field of the command (Illegal Request / INVALID FIELD IN CDB), which usually means that requested page or service action is not supported.
+	pub fn is_invalid_field_in_cdb(&self) -> bool {
+		match *self {
+			Error::Sense(sense::key::SenseKey::IllegalRequest, 0x24, 0x00) => true,
+			_ => false,
+		}
 	}
 }
 
@@ -58,7 +118,7 @@ quick_error! {
 	pub enum ATAError {
 		SCSI(err: Error) {
 			from()
-			from(err: io::Error) -> (Error::IO(err))
+			from(err: io::Error) -> (Error::from(err))
 			display("{}", err)
 		}
 		/// Device does not support ATA PASS-THROUGH command
@@ -68,26 +128,217 @@ quick_error! {
 	}
 }
 
+/// Default command timeout, in seconds
+pub const DEFAULT_TIMEOUT: u32 = 10;
+/// Timeout for START STOP UNIT that waits for the operation to complete, in seconds; spinning up the disk can take quite some time
+pub const START_STOP_TIMEOUT: u32 = 60;
+
+/// SCSI status; see SAM-5, 5.3 Status
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+	Good,
+	/// Sense data describes the error
+	CheckCondition,
+	ConditionMet,
+	/// Logical unit is temporarily unable to accept a command
+	Busy,
+	/// Logical unit is reserved by another initiator
+	ReservationConflict,
+	/// Task set of the logical unit is full
+	TaskSetFull,
+	ACAActive,
+	TaskAborted,
+	Reserved(u8),
+}
+
+impl Status {
+	pub fn from(status: u8) -> Self {
+		use self::Status::*;
+		// bit 0 is vendor-specific in SCSI-2, and some HBAs and older drivers still set it
+		match status & 0xfe {
+			0x00 => Good,
+			0x02 => CheckCondition,
+			0x04 => ConditionMet,
+			0x08 => Busy,
+			0x18 => ReservationConflict,
+			0x28 => TaskSetFull,
+			0x30 => ACAActive,
+			0x40 => TaskAborted,
+			x => Reserved(x),
+		}
+	}
+
+	/// Whether the command was completed by the device, with or without an error
+	fn is_completed(&self) -> bool {
+		use self::Status::*;
+		match *self {
+			Good | CheckCondition | ConditionMet => true,
+			_ => false,
+		}
+	}
+}
+
+impl fmt::Display for Status {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		use self::Status::*;
+		match *self {
+			Good => write!(f, "GOOD"),
+			CheckCondition => write!(f, "CHECK CONDITION"),
+			ConditionMet => write!(f, "CONDITION MET"),
+			Busy => write!(f, "BUSY"),
+			ReservationConflict => write!(f, "RESERVATION CONFLICT"),
+			TaskSetFull => write!(f, "TASK SET FULL"),
+			ACAActive => write!(f, "ACA ACTIVE"),
+			TaskAborted => write!(f, "TASK ABORTED"),
+			Reserved(x) => write!(f, "reserved status {:02x}", x),
+		}
+	}
+}
+
+impl error::Error for Status {
+	fn description(&self) -> &str { "SCSI command not completed" }
+}
+
+/// Failure reported by the host adapter or its driver rather than by the device itself (i.e. command might have never reached the device)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransportError {
+	/// Bus or host adapter was reset while the command was in flight
+	Reset,
+	/// Bus or host adapter is busy
+	BusBusy,
+	/// Driver suggests that the command should be retried
+	SoftError,
+	Timeout,
+	/// Other failures, in platform-specific codes (e.g. `host_status` and `driver_status` of SG_IO on Linux, or CAM status on FreeBSD)
+	Other(u16, u16),
+}
+
+impl fmt::Display for TransportError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		use self::TransportError::*;
+		match *self {
+			Reset => write!(f, "bus or host adapter reset"),
+			BusBusy => write!(f, "bus busy"),
+			SoftError => write!(f, "soft error, retry requested"),
+			Timeout => write!(f, "command timed out"),
+			Other(host, driver) => write!(f, "transport error {:02x}/{:02x}", host, driver),
+		}
+	}
+}
+
+impl error::Error for TransportError {
+	fn description(&self) -> &str { "SCSI transport error" }
+}
+
+/// Tuple of `(status, transport error, sense, data)`, as returned by platform-specific implementations of `do_platform_cmd`
+pub(crate) type PlatformResponse = (u8, Option<TransportError>, Vec<u8>, Vec<u8>);
+
 #[derive(Debug)]
 pub struct SCSIDevice {
 	device: Device,
+	retry: RetryPolicy,
+	timeouts: HashMap<(u8, Option<u16>), u32>,
 }
 
 impl SCSIDevice {
+	/// Wraps `device`, using [default retry policy](retry/struct.RetryPolicy.html#impl-Default).
 	pub fn new(device: Device) -> Self {
-		Self { device }
+		Self { device, retry: RetryPolicy::default(), timeouts: HashMap::new() }
+	}
+
+	/**
+	Sets timeouts (in seconds) for commands issued with [do_cmd](#method.do_cmd) (i.e. for most of the commands), replacing the default of [`DEFAULT_TIMEOUT`](constant.DEFAULT_TIMEOUT.html) seconds.
+
+	Keys are tuples of `(opcode, service action)`, where service action is `None` for commands that do not have any. Use [`Commands::recommended_timeouts`](commands/trait.Commands.html#method.recommended_timeouts) to obtain timeouts that device itself recommends.
+	*/
+	pub fn set_command_timeouts(&mut self, timeouts: HashMap<(u8, Option<u16>), u32>) {
+		self.timeouts = timeouts;
+	}
+
+	/// Returns timeout for the command `cmd`, in seconds.
+	fn command_timeout(&self, cmd: &[u8]) -> u32 {
+		if cmd.is_empty() { return DEFAULT_TIMEOUT; }
+
+		// for commands with service actions (e.g. MAINTENANCE IN or SERVICE ACTION IN(16)) it's in the lower bits of byte 1
+		// TODO variable length CDBs (7Fh), with service action in bytes 8–9
+		self.timeouts.get(&(cmd[0], None))
+			.or_else(|| cmd.get(1).and_then(|sa| self.timeouts.get(&(cmd[0], Some((sa & 0b1_1111) as u16)))))
+			.cloned()
+			.unwrap_or(DEFAULT_TIMEOUT)
+	}
+
+	/// Sets policy for retrying commands that failed for transient reasons.
+	pub fn set_retry_policy(&mut self, policy: RetryPolicy) {
+		self.retry = policy;
+	}
+
+	pub fn retry_policy(&self) -> &RetryPolicy {
+		&self.retry
+	}
+
+	/**
+	Executes `cmd` (retrying it according to the retry policy) and returns tuple of `(sense, data)`; `data` is what is sent to the device for `Direction::To`, or the buffer to read data into otherwise.
+
+	Commands that were not completed by the device (e.g. because it is busy, or is reserved by another initiator) result in an `io::Error` of kind `Other` that wraps [`Status`](enum.Status.html); it becomes [`Error::Status`](enum.Error.html#variant.Status) once converted into `scsi::Error`. Failures of the host adapter or its driver result in an `io::Error` of kind `Other` that wraps [`TransportError`](enum.TransportError.html).
+	*/
+	fn do_cmd_retry(&self, cmd: &[u8], dir: Direction, sense_len: usize, data: &[u8], timeout: u32) -> Result<(Vec<u8>, Vec<u8>), io::Error> {
+		let mut attempt = 1;
+		loop {
+			// this one is implemented in `mod {linux,freebsd}`
+			let (status, transport, sense, data) = Self::do_platform_cmd(self, cmd, dir, sense_len, data.to_vec(), timeout)?;
+			let status = Status::from(status);
+
+			debug!("SCSI status: {}", status);
+			debug!("SCSI autosense: {}", hexdump_8(&sense));
+
+			// status and sense are meaningless if the command failed on its way to the device
+			if let Some(err) = transport {
+				debug!("SCSI transport error: {}", err);
+
+				if attempt < self.retry.max_attempts && self.retry.transport.contains(&err) {
+					let delay = self.retry.delay(attempt);
+					info!("SCSI cmd failed ({}), retrying in {:?} (attempt {} of {})",
+						err, delay, attempt + 1, self.retry.max_attempts);
+
+					thread::sleep(delay);
+					attempt += 1;
+					continue;
+				}
+
+				return Err(io::Error::new(io::ErrorKind::Other, err));
+			}
+
+			if attempt < self.retry.max_attempts && self.retry.should_retry(status, &sense) {
+				let delay = self.retry.delay(attempt);
+				info!("SCSI cmd failed (status {}, sense {}), retrying in {:?} (attempt {} of {})",
+					status, hexdump_8(&sense), delay, attempt + 1, self.retry.max_attempts);
+
+				thread::sleep(delay);
+				attempt += 1;
+				continue;
+			}
+
+			if !status.is_completed() {
+				return Err(io::Error::new(io::ErrorKind::Other, status));
+			}
+
+			return Ok((sense, data));
+		}
 	}
 
 	// thin wrapper against platform-specific implementation, mainly exists to provide consistent logging between platforms
 	/// Executes `cmd` and returns tuple of `(sense, data)`.
 	pub fn do_cmd(&self, cmd: &[u8], dir: Direction, sense_len: usize, data_len: usize) -> Result<(Vec<u8>, Vec<u8>), io::Error> {
-		info!("SCSI cmd: dir={:?} cmd={:?}", dir, cmd);
+		self.do_cmd_timeout(cmd, dir, sense_len, data_len, self.command_timeout(cmd))
+	}
 
-		// this one is implemented in `mod {linux,freebsd}`
-		let ret = Self::do_platform_cmd(self, cmd, dir, sense_len, data_len);
+	/// Same as [do_cmd](#method.do_cmd), but with the command timeout (in seconds) specified explicitly; useful for commands that take a long time to complete.
+	pub fn do_cmd_timeout(&self, cmd: &[u8], dir: Direction, sense_len: usize, data_len: usize, timeout: u32) -> Result<(Vec<u8>, Vec<u8>), io::Error> {
+		info!("SCSI cmd: dir={:?} cmd={:?} timeout={:?}", dir, cmd, timeout);
+
+		let ret = self.do_cmd_retry(cmd, dir, sense_len, &vec![0; data_len], timeout);
 		match ret {
-			Ok((ref sense, ref data)) => {
-				debug!("SCSI autosense: {}", hexdump_8(sense));
+			Ok((_, ref data)) => {
 				debug!("SCSI data: {}", hexdump_8(data));
 			},
 			ref err => {
@@ -96,12 +347,74 @@ impl SCSIDevice {
 		}
 		ret
 	}
+
+	/// Executes `cmd` that transfers `data` to the device, and returns sense.
+	pub fn do_cmd_out(&self, cmd: &[u8], data: &[u8], sense_len: usize) -> Result<Vec<u8>, io::Error> {
+		self.do_cmd_out_timeout(cmd, data, sense_len, self.command_timeout(cmd))
+	}
+
+	/// Same as [do_cmd_out](#method.do_cmd_out), but with the command timeout (in seconds) specified explicitly.
+	pub fn do_cmd_out_timeout(&self, cmd: &[u8], data: &[u8], sense_len: usize, timeout: u32) -> Result<Vec<u8>, io::Error> {
+		info!("SCSI cmd: dir={:?} cmd={:?} timeout={:?}", Direction::To, cmd, timeout);
+		debug!("SCSI data out: {}", hexdump_8(data));
+
+		let ret = self.do_cmd_retry(cmd, Direction::To, sense_len, data, timeout);
+		if let Err(ref err) = ret {
+			debug!("SCSI err: {:?}", err);
+		}
+		ret.map(|(sense, _)| sense)
+	}
+}
+
+/**
+Turns current sense into an error.
+
+Returns `Ok(())` if `sense` is empty, describes deferred error, or carries sense key that does not indicate an error (i.e. No Sense, Recovered Error or Completed).
+*/
+pub fn check_sense(sense: &[u8]) -> Result<(), Error> {
+	if sense.is_empty() { return Ok(()); }
+
+	let (key, asc, ascq) = match sense::parse(&sense.to_vec()) {
+		Some((true, sense)) => sense.code(),
+
+		// deferred errors are not related to the command that returned them
+		Some((false, _)) => return Ok(()),
+		// unknown sense format
+		None => return Err(Error::Nonsense),
+	};
+
+	match key {
+		0x00 | 0x01 | 0x0f => Ok(()),
+		key => Err(Error::Sense(sense::key::SenseKey::from(key), asc, ascq)),
+	}
+}
+
+/// Page Control field of MODE SENSE
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PageControl {
+	/// Values that are currently in use
+	Current = 0b00,
+	/// Mask that denotes bits that can be changed with MODE SELECT
+	Changeable = 0b01,
+	/// Values that are in effect after power-on or hard reset, unless saved values are available
+	Default = 0b10,
+	/// Values that were saved into non-volatile memory
+	Saved = 0b11,
 }
 
 // TODO look for non-empty autosense and turn it into errors where appropriate
 pub trait SCSICommon {
 	// XXX DRY
 	fn do_cmd(&self, cmd: &[u8], dir: Direction, sense_len: usize, data_len: usize) -> Result<(Vec<u8>, Vec<u8>), io::Error>;
+	fn do_cmd_timeout(&self, cmd: &[u8], dir: Direction, sense_len: usize, data_len: usize, timeout: u32) -> Result<(Vec<u8>, Vec<u8>), io::Error>;
+	/// Executes `cmd` that transfers `data` to the device, and returns sense; uses [`DEFAULT_TIMEOUT`](constant.DEFAULT_TIMEOUT.html) unless overridden.
+	fn do_cmd_out(&self, cmd: &[u8], data: &[u8], sense_len: usize) -> Result<Vec<u8>, io::Error> {
+		self.do_cmd_out_timeout(cmd, data, sense_len, DEFAULT_TIMEOUT)
+	}
+	/// Same as [do_cmd_out](#method.do_cmd_out), but with the command timeout (in seconds) specified explicitly. Implementors that cannot send data to the device need not override it, as it fails with an error by default.
+	fn do_cmd_out_timeout(&self, _cmd: &[u8], _data: &[u8], _sense_len: usize, _timeout: u32) -> Result<Vec<u8>, io::Error> {
+		Err(io::Error::new(io::ErrorKind::Other, "commands that transfer data to the device are not supported"))
+	}
 
 	fn scsi_inquiry(&self, vital: bool, code: u8) -> Result<(Vec<u8>, Vec<u8>), Error> {
 		info!("issuing INQUIRY: code={:?} vital={:?}", code, vital);
@@ -121,6 +434,71 @@ pub trait SCSICommon {
 		Ok(self.do_cmd(&cmd, Direction::From, 32, alloc)?)
 	}
 
+	/// Issues INQUIRY for VPD page `page`, and returns page data (including the header) if device did not report any error.
+	fn vpd_page(&self, page: u8) -> Result<Vec<u8>, Error> {
+		let (sense, data) = self.scsi_inquiry(true, page)?;
+		check_sense(&sense)?;
+		Ok(data)
+	}
+
+	/// Returns codes of VPD pages that device supports.
+	fn supported_vpd_pages(&self) -> Result<Vec<u8>, Error> {
+		let data = self.vpd_page(0x00)?;
+		vpd::supported_pages(&data).ok_or(Error::InvalidData("parse Supported VPD Pages"))
+	}
+
+	/// Returns unit serial number, as reported in Unit Serial Number VPD page.
+	fn unit_serial_number(&self) -> Result<String, Error> {
+		let data = self.vpd_page(0x80)?;
+		vpd::unit_serial_number(&data).ok_or(Error::InvalidData("parse Unit Serial Number VPD page"))
+	}
+
+	/// Returns designators (WWNs, port numbers, etc.) of the logical unit, target port and target device; see Device Identification VPD page.
+	fn device_identification(&self) -> Result<Vec<device_id::Descriptor>, Error> {
+		let data = self.vpd_page(0x83)?;
+		device_id::parse(&data).ok_or(Error::InvalidData("parse Device Identification VPD page"))
+	}
+
+	/// Returns the identifier of the logical unit (see [`device_id::logical_unit_id`](data/vpd/device_id/fn.logical_unit_id.html)), or `None` if device does not report any suitable designator.
+	fn logical_unit_id(&self) -> Result<Option<device_id::Identifier>, Error> {
+		let descriptors = self.device_identification()?;
+		Ok(device_id::logical_unit_id(&descriptors).cloned())
+	}
+
+	fn extended_inquiry(&self) -> Result<ExtendedInquiry, Error> {
+		let data = self.vpd_page(0x86)?;
+		extended_inquiry::parse(&data).ok_or(Error::InvalidData("parse Extended INQUIRY Data VPD page"))
+	}
+
+	fn mode_page_policy(&self) -> Result<Vec<ModePagePolicy>, Error> {
+		let data = self.vpd_page(0x87)?;
+		mode_page_policy::parse(&data).ok_or(Error::InvalidData("parse Mode Page Policy VPD page"))
+	}
+
+	/// Returns rotation rate, form factor and other characteristics of a block device (SBC); see Block Device Characteristics VPD page.
+	fn block_device_characteristics(&self) -> Result<BlockDeviceCharacteristics, Error> {
+		let data = self.vpd_page(0xb1)?;
+		block_device_characteristics::parse(&data).ok_or(Error::InvalidData("parse Block Device Characteristics VPD page"))
+	}
+
+	/// Returns description of the SCSI to ATA Translation layer and the ATA device behind it; see ATA Information VPD page.
+	fn ata_information(&self) -> Result<ATAInformation, Error> {
+		let data = self.vpd_page(0x89)?;
+		ata_information::parse(&data).ok_or(Error::InvalidData("parse ATA Information VPD page"))
+	}
+
+	/**
+	Tells whether this is an ATA device behind SCSI to ATA Translation layer, without issuing ATA PASS-THROUGH.
+
+	Returns ATA Information VPD page if device reports it, or `None` if device does not support this page (which does not necessarily mean it is not a SAT device; use ATA PASS-THROUGH to find out).
+	*/
+	fn sat_information(&self) -> Result<Option<ATAInformation>, Error> {
+		if !self.supported_vpd_pages()?.contains(&0x89) {
+			return Ok(None);
+		}
+		Ok(Some(self.ata_information()?))
+	}
+
 	/// returns tuple of (sense, logical block address, block length in bytes)
 	fn read_capacity_10(&self, lba: Option<u32>) -> Result<(Vec<u8>, u32, u32), Error> {
 		info!("issuing READ CAPACITY(10): lba={:?}", lba);
@@ -205,6 +583,428 @@ pub trait SCSICommon {
 		Ok(self.do_cmd(&cmd, Direction::From, 32, alloc)?)
 	}
 
+	/**
+	Executes LOG SELECT command, returns sense.
+
+	Arguments are:
+
+	- `pcr`: parameter code reset; reset all the parameters of the page (or of all the pages, if `page` is 0) to their default values
+	- `save_params`: also save parameters marked as saveable into non-volatile, vendor-specific location (might not be supported)
+	- `default`, `threshold`: which values to reset or set, see [log_sense](#method.log_sense)
+	- `page`, `subpage`: log page to reset
+	- `data`: parameter list, i.e. log page with parameters to set (see [`log_page::compose`](data/log_page/fn.compose.html)); should be empty if `pcr` is set
+	*/
+	#[cfg_attr(feature = "cargo-clippy", allow(too_many_arguments))]
+	fn log_select(&self, pcr: bool, save_params: bool, default: bool, threshold: bool, page: u8, subpage: u8, data: &[u8]) -> Result<Vec<u8>, Error> {
+		info!("issuing LOG SELECT: page={:?} subpage={:?} pcr={:?} save_params={:?} default={:?} threshold={:?} data_len={:?}", page, subpage, pcr, save_params, default, threshold, data.len());
+
+		// Page Control field
+		let pc = match (default, threshold) {
+			(false, true) => 0b00, // > threshold values
+			(false, false) => 0b01, // > cumulative values
+			(true, true) => 0b10, // > default threshold values
+			(true, false) => 0b11, // > default cumulative values
+		};
+
+		// TODO Err() if data.len() > 0xffff
+		let cmd: [u8; 10] = [
+			0x4c, // opcode
+			if pcr {0b10} else {0} + if save_params {0b1} else {0}, // [reserved × 6][pcr][sp]
+			// TODO Err() if page >= 0b1'000'000
+			(pc << 6) + page,
+			subpage,
+			0, // reserved
+			0, // reserved
+			0, // reserved
+			(data.len() >> 8) as u8,
+			(data.len() & 0xff) as u8,
+			0, // control (XXX what's that?!)
+		];
+
+		if data.is_empty() {
+			Ok(self.do_cmd(&cmd, Direction::None, 32, 0)?.0)
+		} else {
+			Ok(self.do_cmd_out(&cmd, data, 32)?)
+		}
+	}
+
+	/**
+	Executes READ DEFECT DATA (10) command, returning tuple of `(sense, data)`.
+
+	Arguments are:
+
+	- `plist`, `glist`: whether to return primary and/or grown defect list,
+	- `format`: requested address descriptor format (see [`defect::Format`](data/defect/enum.Format.html)),
+	- `alloc`: allocation length; use 4 to only query the header, which includes the length of the list.
+	*/
+	fn read_defect_data_10(&self, plist: bool, glist: bool, format: u8, alloc: u16) -> Result<(Vec<u8>, Vec<u8>), Error> {
+		info!("issuing READ DEFECT DATA(10): plist={:?} glist={:?} format={:?} alloc={:?}", plist, glist, format, alloc);
+
+		let cmd: [u8; 10] = [
+			0x37, // opcode
+			0, // reserved
+			if plist {0b1_0000} else {0} + if glist {0b1000} else {0} + (format & 0b111), // [reserved × 3][req_plist][req_glist][format × 3]
+			0, // reserved
+			0, // reserved
+			0, // reserved
+			0, // reserved
+			(alloc >> 8) as u8,
+			(alloc & 0xff) as u8,
+			0, // control (XXX what's that?!)
+		];
+
+		Ok(self.do_cmd(&cmd, Direction::From, 32, alloc as usize)?)
+	}
+
+	/**
+	Executes READ DEFECT DATA (12) command, returning tuple of `(sense, data)`.
+
+	Arguments are the same as for [read_defect_data_10](#method.read_defect_data_10), except:
+
+	- `index`: address descriptor index to start the list with,
+	- `alloc`: allocation length; use 8 to only query the header.
+	*/
+	fn read_defect_data_12(&self, plist: bool, glist: bool, format: u8, index: u32, alloc: u32) -> Result<(Vec<u8>, Vec<u8>), Error> {
+		info!("issuing READ DEFECT DATA(12): plist={:?} glist={:?} format={:?} index={:?} alloc={:?}", plist, glist, format, index, alloc);
+
+		let cmd: [u8; 12] = [
+			0xb7, // opcode
+			if plist {0b1_0000} else {0} + if glist {0b1000} else {0} + (format & 0b111), // [reserved × 3][req_plist][req_glist][format × 3]
+			(index >> 24) as u8,
+			((index >> 16) & 0xff) as u8,
+			((index >> 8) & 0xff) as u8,
+			(index & 0xff) as u8,
+			(alloc >> 24) as u8,
+			((alloc >> 16) & 0xff) as u8,
+			((alloc >> 8) & 0xff) as u8,
+			(alloc & 0xff) as u8,
+			0, // reserved
+			0, // control (XXX what's that?!)
+		];
+
+		Ok(self.do_cmd(&cmd, Direction::From, 32, alloc as usize)?)
+	}
+
+	/**
+	Issues REPORT LUNS command.
+
+	`select_report` is 00h for logical units that device can address (except well known logical units), 01h for well known logical units only, and 02h for all logical units.
+	*/
+	fn report_luns(&self, select_report: u8, alloc: u32) -> Result<(Vec<u8>, Vec<u8>), Error> {
+		info!("issuing REPORT LUNS: select_report={:?} alloc={:?}", select_report, alloc);
+
+		let cmd: [u8; 12] = [
+			0xa0, // opcode
+			0, // reserved
+			select_report,
+			0, 0, 0, // reserved
+			(alloc >> 24) as u8,
+			((alloc >> 16) & 0xff) as u8,
+			((alloc >> 8) & 0xff) as u8,
+			(alloc & 0xff) as u8,
+			0, // reserved
+			0, // control (XXX what's that?!)
+		];
+
+		Ok(self.do_cmd(&cmd, Direction::From, 32, alloc as usize)?)
+	}
+
+	/**
+	Issues REPORT SUPPORTED OPERATION CODES command.
+
+	- `rctd`: return command timeouts descriptors
+	- `options`: reporting options; 000b to list all the commands, 001b–011b to describe single command `opcode` (with `service_action`, if applicable)
+	*/
+	fn report_supported_opcodes(&self, rctd: bool, options: u8, opcode: u8, service_action: u16, alloc: u32) -> Result<(Vec<u8>, Vec<u8>), Error> {
+		info!("issuing REPORT SUPPORTED OPERATION CODES: rctd={:?} options={:?} opcode={:?} service_action={:?} alloc={:?}", rctd, options, opcode, service_action, alloc);
+
+		let cmd: [u8; 12] = [
+			0xa3, // opcode: MAINTENANCE IN
+			0x0c, // [reserved × 3][service action × 5]
+			if rctd {0b1000_0000} else {0} + (options & 0b111), // [rctd][reserved × 4][reporting options × 3]
+			opcode,
+			(service_action >> 8) as u8,
+			(service_action & 0xff) as u8,
+			(alloc >> 24) as u8,
+			((alloc >> 16) & 0xff) as u8,
+			((alloc >> 8) & 0xff) as u8,
+			(alloc & 0xff) as u8,
+			0, // reserved
+			0, // control (XXX what's that?!)
+		];
+
+		Ok(self.do_cmd(&cmd, Direction::From, 32, alloc as usize)?)
+	}
+
+	/// Issues REPORT SUPPORTED TASK MANAGEMENT FUNCTIONS command; `repd` requests extended parameter data.
+	fn report_supported_tmf(&self, repd: bool, alloc: u32) -> Result<(Vec<u8>, Vec<u8>), Error> {
+		info!("issuing REPORT SUPPORTED TASK MANAGEMENT FUNCTIONS: repd={:?} alloc={:?}", repd, alloc);
+
+		let cmd: [u8; 12] = [
+			0xa3, // opcode: MAINTENANCE IN
+			0x0d, // [reserved × 3][service action × 5]
+			if repd {0b1000_0000} else {0}, // [repd][reserved × 7]
+			0, 0, 0, // reserved
+			(alloc >> 24) as u8,
+			((alloc >> 16) & 0xff) as u8,
+			((alloc >> 8) & 0xff) as u8,
+			(alloc & 0xff) as u8,
+			0, // reserved
+			0, // control (XXX what's that?!)
+		];
+
+		Ok(self.do_cmd(&cmd, Direction::From, 32, alloc as usize)?)
+	}
+
+	/**
+	Issues PERSISTENT RESERVE IN command.
+
+	`service_action` is 00h for READ KEYS, 01h for READ RESERVATION, 02h for REPORT CAPABILITIES, and 03h for READ FULL STATUS.
+	*/
+	fn persistent_reserve_in(&self, service_action: u8, alloc: u16) -> Result<(Vec<u8>, Vec<u8>), Error> {
+		info!("issuing PERSISTENT RESERVE IN: service_action={:02x} alloc={:?}", service_action, alloc);
+
+		let cmd: [u8; 10] = [
+			0x5e, // opcode
+			service_action & 0b1_1111, // [reserved × 3][service action × 5]
+			0, 0, 0, 0, 0, // reserved
+			(alloc >> 8) as u8,
+			(alloc & 0xff) as u8,
+			0, // control (XXX what's that?!)
+		];
+
+		Ok(self.do_cmd(&cmd, Direction::From, 32, alloc as usize)?)
+	}
+
+	/**
+	Executes REQUEST SENSE command, returning tuple of `(sense, data)`, where `data` is the sense data that device reports.
+
+	If `desc` is set, device is asked to return sense data in the descriptor format.
+	*/
+	fn request_sense(&self, desc: bool) -> Result<(Vec<u8>, Vec<u8>), Error> {
+		info!("issuing REQUEST SENSE: desc={:?}", desc);
+
+		const alloc: usize = 252;
+
+		let cmd: [u8; 6] = [
+			0x03, // opcode
+			if desc {1} else {0}, // [reserved × 7][desc]
+			0, // reserved
+			0, // reserved
+			alloc as u8,
+			0, // control (XXX what's that?!)
+		];
+
+		Ok(self.do_cmd(&cmd, Direction::From, 32, alloc)?)
+	}
+
+	/// Executes TEST UNIT READY command, returns sense.
+	fn test_unit_ready(&self) -> Result<Vec<u8>, Error> {
+		info!("issuing TEST UNIT READY");
+
+		let cmd: [u8; 6] = [
+			0x00, // opcode
+			0, // reserved
+			0, // reserved
+			0, // reserved
+			0, // reserved
+			0, // control
+		];
+
+		Ok(self.do_cmd(&cmd, Direction::None, 32, 0)?.0)
+	}
+
+	/**
+	Executes START STOP UNIT command, returns sense.
+
+	Arguments are:
+
+	- `immed`: return as soon as the command is validated, without waiting for the operation to complete,
+	- `power_condition`: POWER CONDITION field (SBC-3, 5.20); `0` means that only `start` and `loej` are processed,
+	- `modifier`: POWER CONDITION MODIFIER field,
+	- `no_flush`: do not flush cache before entering power condition,
+	- `loej`: load (with `start` set) or eject (with `start` cleared) the medium,
+	- `start`: make logical unit ready (spin up), or stop it (spin down).
+
+	Unless `immed` is set, command might take a while (think spinning up the disk), so it is issued with [`START_STOP_TIMEOUT`](constant.START_STOP_TIMEOUT.html).
+	*/
+	fn start_stop_unit(&self, immed: bool, power_condition: u8, modifier: u8, no_flush: bool, loej: bool, start: bool) -> Result<Vec<u8>, Error> {
+		info!("issuing START STOP UNIT: immed={:?} power_condition={:x}h modifier={:x}h no_flush={:?} loej={:?} start={:?}", immed, power_condition, modifier, no_flush, loej, start);
+
+		let cmd: [u8; 6] = [
+			0x1b, // opcode
+			if immed {1} else {0}, // [reserved × 7][immed]
+			0, // reserved
+			modifier & 0b1111, // [reserved × 4][power condition modifier × 4]
+			((power_condition & 0b1111) << 4)
+				+ if no_flush { 1 << 2 } else { 0 }
+				+ if loej { 1 << 1 } else { 0 }
+				+ if start { 1 } else { 0 },
+			0, // control
+		];
+
+		let timeout = if immed { DEFAULT_TIMEOUT } else { START_STOP_TIMEOUT };
+		Ok(self.do_cmd_timeout(&cmd, Direction::None, 32, 0, timeout)?.0)
+	}
+
+	/**
+	Executes MODE SENSE(6) command.
+
+	Arguments are:
+
+	- `dbd`: disable block descriptors, i.e. do not return them with the mode pages
+	- `pc`: which values to return, see [enum PageControl](enum.PageControl.html)
+	- `page`, `subpage`: mode page to return; page `3fh` stands for all mode pages, subpage `ffh` for all subpages
+	*/
+	fn mode_sense_6(&self, dbd: bool, pc: PageControl, page: u8, subpage: u8) -> Result<(Vec<u8>, Vec<u8>), Error> {
+		info!("issuing MODE SENSE(6): page={:?} subpage={:?} pc={:?} dbd={:?}", page, subpage, pc, dbd);
+
+		const alloc: usize = 255;
+
+		let cmd: [u8; 6] = [
+			0x1a, // opcode
+			if dbd {0b1000} else {0}, // [reserved × 4][dbd][reserved × 3]
+			// TODO Err() if page >= 0b1'000'000
+			((pc as u8) << 6) + page,
+			subpage,
+			alloc as u8,
+			0, // control (XXX what's that?!)
+		];
+
+		Ok(self.do_cmd(&cmd, Direction::From, 32, alloc)?)
+	}
+
+	/**
+	Executes MODE SENSE(10) command.
+
+	Arguments are the same as for [mode_sense_6](#method.mode_sense_6), plus:
+
+	- `llbaa`: whether device is allowed to return long LBA block descriptors
+	*/
+	fn mode_sense_10(&self, llbaa: bool, dbd: bool, pc: PageControl, page: u8, subpage: u8) -> Result<(Vec<u8>, Vec<u8>), Error> {
+		info!("issuing MODE SENSE(10): page={:?} subpage={:?} pc={:?} dbd={:?} llbaa={:?}", page, subpage, pc, dbd, llbaa);
+
+		// TODO as u16 argument, not const
+		const alloc: usize = 4096;
+
+		let cmd: [u8; 10] = [
+			0x5a, // opcode
+			if llbaa {0b1_0000} else {0} + if dbd {0b1000} else {0}, // [reserved × 3][llbaa][dbd][reserved × 3]
+			// TODO Err() if page >= 0b1'000'000
+			((pc as u8) << 6) + page,
+			subpage,
+			0, // reserved
+			0, // reserved
+			0, // reserved
+			(alloc >> 8) as u8,
+			(alloc & 0xff) as u8,
+			0, // control (XXX what's that?!)
+		];
+
+		Ok(self.do_cmd(&cmd, Direction::From, 32, alloc)?)
+	}
+
+	/**
+	Executes MODE SELECT(6) command, sending `data` (mode parameter list, see [`mode_page::compose_6`](data/mode_page/fn.compose_6.html)) to the device. Returns sense.
+
+	Arguments are:
+
+	- `pf`: page format; whether mode pages in `data` conform to the standard, or are vendor-specific
+	- `save`: whether to save pages into the non-volatile memory
+	*/
+	fn mode_select_6(&self, pf: bool, save: bool, data: &[u8]) -> Result<Vec<u8>, Error> {
+		info!("issuing MODE SELECT(6): pf={:?} save={:?}", pf, save);
+
+		// TODO Err() if data.len() > 255
+		let cmd: [u8; 6] = [
+			0x15, // opcode
+			if pf {0b1_0000} else {0} + if save {0b1} else {0}, // [reserved × 3][pf][reserved × 3][sp]
+			0, // reserved
+			0, // reserved
+			data.len() as u8,
+			0, // control (XXX what's that?!)
+		];
+
+		Ok(self.do_cmd_out(&cmd, data, 32)?)
+	}
+
+	/// Executes MODE SELECT(10) command; see [mode_select_6](#method.mode_select_6) for arguments, and [`mode_page::compose_10`](data/mode_page/fn.compose_10.html) for `data`.
+	fn mode_select_10(&self, pf: bool, save: bool, data: &[u8]) -> Result<Vec<u8>, Error> {
+		info!("issuing MODE SELECT(10): pf={:?} save={:?}", pf, save);
+
+		// TODO Err() if data.len() > 0xffff
+		let cmd: [u8; 10] = [
+			0x55, // opcode
+			if pf {0b1_0000} else {0} + if save {0b1} else {0}, // [reserved × 3][pf][reserved × 3][sp]
+			0, // reserved
+			0, // reserved
+			0, // reserved
+			0, // reserved
+			0, // reserved
+			(data.len() >> 8) as u8,
+			(data.len() & 0xff) as u8,
+			0, // control (XXX what's that?!)
+		];
+
+		Ok(self.do_cmd_out(&cmd, data, 32)?)
+	}
+
+	/**
+	Executes SEND DIAGNOSTIC command, returning sense.
+
+	Arguments are:
+
+	- `self_test_code`: which self-test to start or abort (see SPC-4, table 219); should be 0 if `self_test` is set
+	- `self_test`: whether to perform device's default self-test
+	- `data`: parameter list (i.e. diagnostic page) to send to the device; PF bit is set if it is not empty
+	- `timeout`: command timeout, in seconds; foreground self-tests only return after they are done, which might take a long time
+
+	Note that DEVOFFL and UNITOFFL bits are never set, i.e. self-tests are not allowed to affect other logical units or to alter the medium.
+	*/
+	fn send_diagnostic(&self, self_test_code: u8, self_test: bool, data: &[u8], timeout: u32) -> Result<Vec<u8>, Error> {
+		info!("issuing SEND DIAGNOSTIC: self_test_code={:?} self_test={:?} data_len={:?}", self_test_code, self_test, data.len());
+
+		// TODO Err() if data.len() > 0xffff
+		let cmd: [u8; 6] = [
+			0x1d, // opcode
+			// [self-test code × 3][pf][reserved][selftest][devoffl][unitoffl]
+			(self_test_code << 5)
+				+ if !data.is_empty() {0b1_0000} else {0}
+				+ if self_test {0b100} else {0},
+			0, // reserved
+			(data.len() >> 8) as u8,
+			(data.len() & 0xff) as u8,
+			0, // control (XXX what's that?!)
+		];
+
+		if data.is_empty() {
+			let (sense, _) = self.do_cmd_timeout(&cmd, Direction::None, 32, 0, timeout)?;
+			Ok(sense)
+		} else {
+			Ok(self.do_cmd_out_timeout(&cmd, data, 32, timeout)?)
+		}
+	}
+
+	/**
+	Issues RECEIVE DIAGNOSTIC RESULTS command.
+
+	With `pcv` (page code valid) set, device returns diagnostic page `page`; otherwise it returns the results of the last SEND DIAGNOSTIC command.
+	*/
+	fn receive_diagnostic_results(&self, pcv: bool, page: u8, alloc: u16) -> Result<(Vec<u8>, Vec<u8>), Error> {
+		info!("issuing RECEIVE DIAGNOSTIC RESULTS: pcv={:?} page={:02x} alloc={:?}", pcv, page, alloc);
+
+		let cmd: [u8; 6] = [
+			0x1c, // opcode
+			if pcv {1} else {0}, // [reserved × 7][pcv]
+			page,
+			(alloc >> 8) as u8,
+			(alloc & 0xff) as u8,
+			0, // control (XXX what's that?!)
+		];
+
+		Ok(self.do_cmd(&cmd, Direction::From, 32, alloc as usize)?)
+	}
+
 	fn ata_pass_through_16(&self, dir: Direction, regs: &ata::RegistersWrite) -> Result<(ata::RegistersRead, Vec<u8>), ATAError> {
 		info!("issuing ATA PASS-THROUGH (16): dir={:?} regs={:?}", dir, regs);
 
@@ -239,66 +1039,28 @@ pub trait SCSICommon {
 
 		let (sense, data) = self.do_cmd(&ata_cmd, Direction::From, 32, 512)?;
 
-		// FIXME this block is full of super-awkward patterns
-		let descriptors = match sense::parse(&sense) {
-			// current sense in the descriptor format
-			Some((true, sense::Sense::Descriptor(sense::DescriptorData {
-				descriptors,
-				// Recovered Error / ATA PASS THROUGH INFORMATION AVAILABLE
-				key: 0x01, asc: 0x00, ascq: 0x1D,
-				..
-			}))) => {
-				descriptors
-			},
-
-			Some((true, sense::Sense::Fixed(sense::FixedData::Valid {
-				// Illegal Request / INVALID COMMAND OPERATION CODE
-				key: 0x05, asc: 0x20, ascq: 0x00, ..
-			}))) => {
-				return Err(ATAError::NotSupported);
-			},
-
-			Some((true, sense::Sense::Fixed(sense::FixedData::Valid {
-				key, asc, ascq, ..
-			})))
-			| Some((true, sense::Sense::Descriptor(sense::DescriptorData {
-				key, asc, ascq, ..
-			})))
-			=> {
-				// unexpected sense
-				return Err(Error::Sense(sense::key::SenseKey::from(key), asc, ascq))?;
-			},
-
-			Some((true, sense::Sense::Fixed(sense::FixedData::Invalid(_)))) => {
-				// invalid sense
-				return Err(Error::Nonsense)?;
-			},
-
+		let sense = match sense::parse(&sense) {
+			Some((true, sense)) => sense,
 			Some((false, _)) | None => {
 				// no (current) sense
 				return Err(ATAError::NoRegisters);
 			},
 		};
 
-		for desc in descriptors {
-			if desc.code != 0x09 { continue; }
-			if desc.data.len() != 12 { continue; }
-
-			let d = desc.data;
+		match sense.code() {
+			// Recovered Error / ATA PASS THROUGH INFORMATION AVAILABLE
+			(0x01, 0x00, 0x1D) => (),
+			// Illegal Request / INVALID COMMAND OPERATION CODE
+			(0x05, 0x20, 0x00) => return Err(ATAError::NotSupported),
+			// unexpected sense
+			(key, asc, ascq) => return Err(Error::Sense(sense::key::SenseKey::from(key), asc, ascq))?,
+		}
 
+		for desc in sense.descriptors() {
 			// TODO? EXTEND bit, ATA PASS-THROUGH 12 vs 16
-			return Ok((ata::RegistersRead {
-				error: d[1],
-
-				sector_count: d[3],
-
-				sector: d[5],
-				cyl_low: d[7],
-				cyl_high: d[9],
-				device: d[10],
-
-				status: d[11],
-			}, data))
+			if let sense::DescriptorType::ATAStatusReturn { registers, .. } = desc {
+				return Ok((registers, data));
+			}
 		}
 
 		return Err(ATAError::NoRegisters);
@@ -310,4 +1072,13 @@ impl SCSICommon for SCSIDevice {
 	fn do_cmd(&self, cmd: &[u8], dir: Direction, sense_len: usize, data_len: usize) -> Result<(Vec<u8>, Vec<u8>), io::Error> {
 		Self::do_cmd(self, cmd, dir, sense_len, data_len)
 	}
+	fn do_cmd_timeout(&self, cmd: &[u8], dir: Direction, sense_len: usize, data_len: usize, timeout: u32) -> Result<(Vec<u8>, Vec<u8>), io::Error> {
+		Self::do_cmd_timeout(self, cmd, dir, sense_len, data_len, timeout)
+	}
+	fn do_cmd_out(&self, cmd: &[u8], data: &[u8], sense_len: usize) -> Result<Vec<u8>, io::Error> {
+		Self::do_cmd_out(self, cmd, data, sense_len)
+	}
+	fn do_cmd_out_timeout(&self, cmd: &[u8], data: &[u8], sense_len: usize, timeout: u32) -> Result<Vec<u8>, io::Error> {
+		Self::do_cmd_out_timeout(self, cmd, data, sense_len, timeout)
+	}
 }
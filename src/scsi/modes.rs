@@ -0,0 +1,190 @@
+/*!
+Functions implementing typical mode page queries and updates
+
+## Example
+
+```ignore
+use hdd::Device;
+use hdd::scsi::{SCSIDevice, PageControl};
+use hdd::scsi::modes::Modes;
+use hdd::scsi::data::mode_page::caching::Caching;
+
+...
+
+let mut caching: Caching = dev.read_mode_page(PageControl::Current).unwrap();
+if !caching.wce {
+	caching.wce = true;
+	// only bits that are reported as changeable are altered; `true` also saves the page into the non-volatile memory
+	dev.write_mode_page(&caching, true).unwrap();
+}
+```
+*/
+
+use scsi;
+use scsi::{SCSIDevice, SCSICommon, PageControl};
+use scsi::data::mode_page::{self, ModePage};
+use scsi::data::mode_page::error_recovery::ErrorRecovery;
+use scsi::data::mode_page::caching::Caching;
+use scsi::data::mode_page::control::Control;
+use scsi::data::mode_page::power_condition::PowerCondition;
+use scsi::data::mode_page::informational_exceptions::{InformationalExceptions, MRIE};
+use ata::data::id::Ternary;
+
+quick_error! {
+	#[derive(Debug)]
+	pub enum Error {
+		NotSupported {}
+		SCSI(err: scsi::Error) {
+			from()
+			display("{}", err)
+		}
+		/// failed to parse page data
+		InvalidData(what: &'static str) {
+			display("Unable to {}", what)
+		}
+	}
+}
+
+fn into_error(err: scsi::Error) -> Error {
+	// INVALID FIELD IN CDB usually means that the page is not supported
+	if err.is_invalid_field_in_cdb() {
+		Error::NotSupported
+	} else {
+		Error::SCSI(err)
+	}
+}
+
+/// See [module documentation](index.html).
+pub trait Modes: SCSICommon {
+	/**
+	Issues MODE SENSE for a single page, without block descriptors, and returns it as is.
+
+	MODE SENSE(10) is tried first; if device does not support it, MODE SENSE(6) is used instead.
+	*/
+	fn mode_page(&self, page: u8, subpage: u8, pc: PageControl) -> Result<mode_page::Page, Error> {
+		info!("querying mode page {:02x}/{:02x} ({:?})", page, subpage, pc);
+
+		let (sense, data) = self.mode_sense_10(false, true, pc, page, subpage)?;
+		let params = match scsi::check_sense(&sense) {
+			Ok(()) => mode_page::parse_10(&data),
+			Err(ref err) if err.is_invalid_opcode() => {
+				info!("MODE SENSE(10) is not supported, falling back to MODE SENSE(6)");
+
+				let (sense, data) = self.mode_sense_6(true, pc, page, subpage)?;
+				scsi::check_sense(&sense).map_err(into_error)?;
+				mode_page::parse_6(&data)
+			},
+			Err(err) => return Err(into_error(err)),
+		};
+		let params = params.ok_or(Error::InvalidData("parse mode parameters"))?;
+
+		params.pages.into_iter()
+			.find(|p| p.page == page && p.subpage.unwrap_or(0) == subpage)
+			.ok_or(Error::InvalidData("find requested page in the mode parameters"))
+	}
+
+	/**
+	Issues MODE SELECT for a single page, without block descriptors.
+
+	MODE SELECT(10) is tried first; if device does not support it, MODE SELECT(6) is used instead.
+
+	Note that `page` is sent as is; use [write_mode_page](#method.write_mode_page) to make sure only changeable bits are altered.
+	*/
+	fn select_mode_page(&self, page: &mode_page::Page, save: bool) -> Result<(), Error> {
+		info!("updating mode page {:02x}/{:02x} (save={:?})", page.page, page.subpage.unwrap_or(0), save);
+
+		let sense = self.mode_select_10(true, save, &mode_page::compose_10(::std::slice::from_ref(page)))?;
+		match scsi::check_sense(&sense) {
+			Ok(()) => Ok(()),
+			Err(ref err) if err.is_invalid_opcode() => {
+				info!("MODE SELECT(10) is not supported, falling back to MODE SELECT(6)");
+
+				let sense = self.mode_select_6(true, save, &mode_page::compose_6(::std::slice::from_ref(page)))?;
+				scsi::check_sense(&sense).map_err(into_error)
+			},
+			Err(err) => Err(into_error(err)),
+		}
+	}
+
+	/// Reads and interprets mode page represented by type `P`.
+	fn read_mode_page<P: ModePage>(&self, pc: PageControl) -> Result<P, Error> {
+		let (page, subpage) = P::code();
+		let page = self.mode_page(page, subpage, pc)?;
+		P::parse(&page.data).ok_or(Error::InvalidData("parse mode page"))
+	}
+
+	/**
+	Writes `page` back to the device.
+
+	Current values of the page are updated with those found in `page`, but only for the bits that device reports as changeable; the rest of the bits are left intact.
+	If `save` is set, the page is also saved into the non-volatile memory.
+	*/
+	fn write_mode_page<P: ModePage>(&self, page: &P, save: bool) -> Result<(), Error> {
+		let (code, subcode) = P::code();
+
+		let mut current = self.mode_page(code, subcode, PageControl::Current)?;
+		// `ModePage::write` expects the page to be at least as long as `ModePage::parse` does
+		if P::parse(&current.data).is_none() {
+			return Err(Error::InvalidData("parse mode page"));
+		}
+		let changeable = self.mode_page(code, subcode, PageControl::Changeable)?;
+
+		let mut new = current.data.clone();
+		page.write(&mut new);
+		current.data = mode_page::apply_changeable(&current.data, &new, &changeable.data);
+
+		self.select_mode_page(&current, save)
+	}
+
+	fn error_recovery(&self, pc: PageControl) -> Result<ErrorRecovery, Error> {
+		self.read_mode_page(pc)
+	}
+	fn caching(&self, pc: PageControl) -> Result<Caching, Error> {
+		self.read_mode_page(pc)
+	}
+	fn control(&self, pc: PageControl) -> Result<Control, Error> {
+		self.read_mode_page(pc)
+	}
+	fn power_condition(&self, pc: PageControl) -> Result<PowerCondition, Error> {
+		self.read_mode_page(pc)
+	}
+	fn informational_exceptions_control(&self, pc: PageControl) -> Result<InformationalExceptions, Error> {
+		self.read_mode_page(pc)
+	}
+
+	/**
+	Tells whether device reports informational exceptions (e.g. failure prediction threshold exceeded conditions), which is SCSI's counterpart of ATA S.M.A.R.T. feature set.
+
+	Returns `Ternary::Unsupported` if device does not support Informational Exceptions Control mode page.
+	*/
+	fn informational_exceptions_status(&self) -> Result<Ternary, Error> {
+		info!("querying informational exceptions status");
+
+		match self.informational_exceptions_control(PageControl::Current) {
+			Ok(ie) => Ok(ie.status()),
+			Err(Error::NotSupported) => Ok(Ternary::Unsupported),
+			Err(err) => Err(err),
+		}
+	}
+
+	/**
+	Enables or disables reporting of informational exceptions; if `save` is set, this setting persists across power cycles.
+
+	Enabling exception reporting also enables warnings (EWASC bit), and, if reporting method is set to "no reporting", changes it to "only report on request", which is what smartmontools does as well.
+	*/
+	fn set_informational_exceptions(&self, enable: bool, save: bool) -> Result<(), Error> {
+		info!("setting informational exceptions reporting: enable={:?} save={:?}", enable, save);
+
+		let mut ie = self.informational_exceptions_control(PageControl::Current)?;
+
+		ie.dexcpt = !enable;
+		ie.ewasc = enable;
+		if enable && ie.mrie == MRIE::NoReporting {
+			ie.mrie = MRIE::OnRequest;
+		}
+
+		self.write_mode_page(&ie, save)
+	}
+}
+
+impl Modes for SCSIDevice {}
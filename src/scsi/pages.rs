@@ -8,22 +8,23 @@ The reason why this is implemented as a wrapper type instead of a trait is becau
 ```
 use hdd::Device;
 use hdd::scsi::SCSIDevice;
-use hdd::scsi::pages::{Pages, page_name};
+use hdd::scsi::pages::{SCSIPages, page_name};
 
 ...
 
-let pages = dev.supported_pages().unwrap();
+let mut pages = SCSIPages::new(&dev);
 
-if pages.contains(0x03) {
+if pages.is_supported(0x03, 0x00).unwrap() {
 	println!("{}:", page_name(0x03));
-	println!("{:#?}\n", dev.read_error_counters()),
+	println!("{:#?}\n", pages.read_error_counters());
 }
 ```
 */
 
 use scsi;
 use scsi::{SCSIDevice, SCSICommon};
-use scsi::data::log_page;
+use scsi::data::{inquiry, log_page, log_catalog, sense};
+use scsi::data::vendor_log_page::{self, VendorPage};
 
 extern crate byteorder;
 use byteorder::{ReadBytesExt, BigEndian};
@@ -52,9 +53,21 @@ pub enum ErrorCounter {
 
 #[derive(Debug)]
 pub struct Date {
-	// TODO u8, u16
-	pub week: String,
-	pub year: String,
+	pub week: u8,
+	pub year: u16,
+}
+
+impl Date {
+	/// Parses date encoded as 4 ASCII digits of the year followed by 2 ASCII digits of the week; returns `None` if the date is not set (i.e. all spaces) or is malformed
+	fn parse(data: &[u8]) -> Option<Self> {
+		let number = |data: &[u8]| ::std::str::from_utf8(data).ok()
+			.and_then(|s| s.trim().parse::<u16>().ok());
+
+		match (number(&data[0..4]), number(&data[4..6])) {
+			(Some(year), Some(week @ 1 ... 53)) => Some(Date { year, week: week as u8 }),
+			_ => None,
+		}
+	}
 }
 
 #[derive(Debug)]
@@ -102,23 +115,255 @@ pub struct InformationalException {
 	pub vendor_specific: Vec<u8>,
 }
 
+#[derive(Debug)]
+pub enum BackgroundScanStatus {
+	NoScansActive,
+	MediumScanActive,
+	PreScanActive,
+	HaltedFatalError,
+	/// Halted due to a vendor-specific pattern of errors
+	HaltedErrorPattern,
+	/// Halted due to medium formatted without P-List
+	HaltedNoPList,
+	HaltedVendorSpecific,
+	/// Halted due to temperature out of allowable range
+	HaltedTemperature,
+	/// Background scan is enabled, but no scan is active until BMS interval timer expires
+	WaitingForTimer,
+	/// Halted because scan results list is full
+	HaltedListFull,
+	/// Halted because pre-scan time limit timer expired
+	HaltedPreScanTimeLimit,
+	Reserved(u8),
+}
+
+#[derive(Debug)]
+pub enum ReassignStatus {
+	/// LBA has not yet been reassigned; requires WRITE or REASSIGN BLOCKS
+	NotReassigned,
+	/// Device successfully reassigned the LBA
+	Reassigned,
+	/// Device failed to reassign the LBA
+	ReassignFailed,
+	/// Error was recovered by rewriting the LBA in place
+	RecoveredInPlace,
+	/// Application client reassigned the LBA, data is valid
+	ReassignedByHost,
+	/// Application client reassigned the LBA, data is not valid
+	ReassignedByHostNoData,
+	/// Application client failed to reassign the LBA
+	ReassignByHostFailed,
+	Reserved(u8),
+}
+
+#[derive(Debug)]
+pub struct BackgroundScanResult {
+	/// Accumulated power on time when the error was found, in minutes
+	pub power_on_minutes: u32,
+	pub reassign_status: ReassignStatus,
+	pub sense_key: u8,
+	pub sense_asc: u8,
+	pub sense_ascq: u8,
+	pub vendor_specific: Vec<u8>,
+	pub lba: u64,
+}
+
+#[derive(Debug)]
+pub struct BackgroundScan {
+	/// Accumulated power on time, in minutes
+	pub power_on_minutes: u32,
+	pub status: BackgroundScanStatus,
+	/// Number of background scans (both pre-scans and medium scans) performed
+	pub scans_performed: u16,
+	/// Progress of the current (or the last) scan, in percent
+	pub progress: f32,
+	pub medium_scans_performed: Option<u16>,
+	pub results: Vec<BackgroundScanResult>,
+}
+
+#[derive(Debug)]
+pub struct PendingDefect {
+	/// Accumulated power on time when the defect was found, in hours
+	pub power_on_hours: u32,
+	pub lba: u64,
+}
+
+#[derive(Debug)]
+pub struct PendingDefects {
+	/// Number of LBAs awaiting reassignment
+	pub count: u32,
+	pub defects: Vec<PendingDefect>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttachedDevice {
+	NoDevice,
+	EndDevice,
+	Expander,
+	/// Fanout expander device (obsolete since SAS-2)
+	FanoutExpander,
+	Reserved(u8),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LinkRate {
+	/// Phy is enabled, but link rate is unknown
+	Unknown,
+	Disabled,
+	/// Phy is enabled, but speed negotiation failed
+	NegotiationFailed,
+	/// SATA device is attached, but it is waiting for spinup hold to be released
+	SpinupHold,
+	/// Phy is attached to a port selector
+	PortSelector,
+	ResetInProgress,
+	/// Phy is attached to an unsupported phy
+	UnsupportedPhy,
+	/// Negotiated link rate, in Gbit/s
+	Rate(f32),
+	Reserved(u8),
+}
+
+#[derive(Debug)]
+pub struct SASPhy {
+	pub id: u8,
+	pub attached_device: AttachedDevice,
+	/// Reason code for the last link reset of the attached phy
+	pub attached_reason: u8,
+	/// Reason code for the last link reset of this phy
+	pub reason: u8,
+	pub link_rate: LinkRate,
+	pub attached_ssp_initiator: bool,
+	pub attached_stp_initiator: bool,
+	pub attached_smp_initiator: bool,
+	pub attached_ssp_target: bool,
+	pub attached_stp_target: bool,
+	pub attached_smp_target: bool,
+	pub sas_address: u64,
+	pub attached_sas_address: u64,
+	pub attached_phy_id: u8,
+	pub invalid_dwords: u32,
+	pub running_disparity_errors: u32,
+	pub loss_of_dword_sync: u32,
+	pub phy_reset_problems: u32,
+}
+
+#[derive(Debug)]
+pub struct SASPort {
+	/// Relative target port identifier
+	pub id: u16,
+	pub generation: u8,
+	pub phys: Vec<SASPhy>,
+}
+
+#[derive(Debug)]
+pub struct GeneralStatistics {
+	pub read_commands: u64,
+	pub write_commands: u64,
+	/// Number of logical blocks received, i.e. written
+	pub blocks_received: u64,
+	/// Number of logical blocks transmitted, i.e. read
+	pub blocks_transmitted: u64,
+	/// Time spent processing read commands, in time intervals (see `time_interval`)
+	pub read_processing_intervals: u64,
+	/// Time spent processing write commands, in time intervals (see `time_interval`)
+	pub write_processing_intervals: u64,
+	pub weighted_commands: u64,
+	pub weighted_processing_intervals: u64,
+	/// Time spent idle, in time intervals (see `time_interval`)
+	pub idle_intervals: Option<u64>,
+	/// Length of the time interval, in seconds
+	pub time_interval: Option<f64>,
+}
+
+#[derive(Debug)]
+pub struct Utilization {
+	/// Percentage of the rated workload that the device has already been used for, over its lifetime
+	pub workload_utilization: Option<f32>,
+	/// Rate at which the device uses its rated workload, based on date and time, in percent; 100% means that the device is used exactly at its rated rate
+	pub usage_rate: Option<u8>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnvironmentalSensor {
+	/// Temperature sensor with a given index; values are in °C
+	Temperature(u8),
+	/// Relative humidity sensor with a given index; values are in percent
+	RelativeHumidity(u8),
+	Reserved(u16),
+}
+
+impl EnvironmentalSensor {
+	fn from(code: u16) -> Self {
+		use self::EnvironmentalSensor::*;
+		match code {
+			0x0000...0x00ff => Temperature(code as u8),
+			0x0100...0x01ff => RelativeHumidity((code & 0xff) as u8),
+			x => Reserved(x),
+		}
+	}
+
+	/// Interprets raw reading, returns `None` if the value is reported as invalid
+	fn value(&self, x: u8) -> Option<i8> {
+		use self::EnvironmentalSensor::*;
+		match (*self, x) {
+			(Temperature(_), 0x80) => None,
+			(Temperature(_), x) => Some(x as i8),
+			(RelativeHumidity(_), x @ 0 ... 100) => Some(x as i8),
+			(RelativeHumidity(_), _) => None,
+			(Reserved(_), _) => None,
+		}
+	}
+}
+
+#[derive(Debug)]
+pub struct EnvironmentalReport {
+	pub sensor: EnvironmentalSensor,
+	pub current: Option<i8>,
+	pub lifetime_max: Option<i8>,
+	pub lifetime_min: Option<i8>,
+	pub max_since_power_on: Option<i8>,
+	pub min_since_power_on: Option<i8>,
+	/// Vendor-specific, e.g. since the last reset of these values
+	pub max_other: Option<i8>,
+	pub min_other: Option<i8>,
+}
+
+/// Thresholds at which device establishes (trigger) or clears (reset) the environmental condition
+#[derive(Debug)]
+pub struct EnvironmentalLimits {
+	pub sensor: EnvironmentalSensor,
+	pub high_critical_trigger: Option<i8>,
+	pub high_critical_reset: Option<i8>,
+	pub low_critical_reset: Option<i8>,
+	pub low_critical_trigger: Option<i8>,
+	pub high_operating_trigger: Option<i8>,
+	pub high_operating_reset: Option<i8>,
+	pub low_operating_reset: Option<i8>,
+	pub low_operating_trigger: Option<i8>,
+}
+
+/// Accumulated number of transitions to each power condition
+#[derive(Debug)]
+pub struct PowerConditionTransitions {
+	pub active: Option<u32>,
+	pub idle_a: Option<u32>,
+	pub idle_b: Option<u32>,
+	pub idle_c: Option<u32>,
+	pub standby_z: Option<u32>,
+	pub standby_y: Option<u32>,
+}
+
 /// For a given page number `page`, return its name
 pub fn page_name(page: u8) -> &'static str {
-	match page {
-		0x00 => "Supported Log Pages",
-		0x02 => "Write Error Counter",
-		0x03 => "Read Error Counter",
-		0x04 => "Read Reverse Error Counter",
-		0x05 => "Verify Error Counter",
-		0x06 => "Non-Medium Error",
-		0x0d => "Temperature",
-		0x0e => "Start-Stop Cycle Counter",
-		0x10 => "Self-Test results",
-		0x2f => "Informational Exceptions",
-		0x30...0x3e => "(Vendor-Specific)",
-		0x3f => "(Reserved)",
-		// TODO Option<>?
-		_ => "?",
+	match log_catalog::page_name(page, 0x00) {
+		Some(name) => name,
+		None => match page {
+			0x30...0x3e => "(Vendor-Specific)",
+			0x3f => "(Reserved)",
+			// TODO Option<>?
+			_ => "?",
+		},
 	}
 }
 
@@ -145,21 +390,109 @@ See [module documentation](index.html) for example.
 #[derive(Debug)]
 pub struct SCSIPages<'a, T: SCSICommon + 'a> {
 	device: &'a T,
-	supported_pages: Option<Vec<u8>>,
+	/// List of `(page, subpage)` tuples
+	supported_pages: Option<Vec<(u8, u8)>>,
+	vendor_pages: Vec<VendorPage>,
+	/// INQUIRY vendor and product identification (to match vendor pages against), and version
+	inquiry: Option<(String, String, inquiry::Version)>,
 }
 
 // TODO non-empty autosense errors
 impl<'a> SCSIPages<'a, SCSIDevice> {
 	pub fn new(device: &'a SCSIDevice) -> Self {
-		Self { device, supported_pages: None }
+		Self {
+			device,
+			supported_pages: None,
+			vendor_pages: vendor_log_page::builtin(),
+			inquiry: None,
+		}
+	}
+
+	/// Registers decoder for a vendor-specific log page; decoders registered later take precedence over the earlier ones (including the built-in ones).
+	pub fn register_vendor_page(&mut self, page: VendorPage) {
+		self.vendor_pages.insert(0, page);
 	}
 
-	pub fn supported_pages(&mut self) -> Result<Vec<u8>, Error> {
+	/// Returns cached INQUIRY vendor, product and version, querying the device if necessary.
+	fn inquiry(&mut self) -> Result<&(String, String, inquiry::Version), Error> {
+		if self.inquiry.is_none() {
+			info!("querying vendor, product and version");
+
+			let (sense, data) = self.device.scsi_inquiry(false, 0)?;
+			scsi::check_sense(&sense)?;
+			if data.len() < 36 {
+				return Err(Error::InvalidData("parse INQUIRY data"));
+			}
+
+			let inquiry = inquiry::parse_inquiry(&data);
+			self.inquiry = Some((inquiry.vendor_id, inquiry.product_id, inquiry.version));
+		}
+
+		// unwrap is safe: inquiry data is here, or function already returned after unsuccessful attempt to update this field
+		Ok(self.inquiry.as_ref().unwrap())
+	}
+
+	/// Returns decoder for the vendor-specific page `page`, subpage `subpage` that matches this device, if there's any.
+	pub fn vendor_page(&mut self, page: u8, subpage: u8) -> Result<Option<VendorPage>, Error> {
+		self.inquiry()?;
+		// unwrap is safe: see above
+		let (ref vendor, ref product, _) = *self.inquiry.as_ref().unwrap();
+
+		Ok(self.vendor_pages.iter()
+			.find(|p| p.page == page && p.subpage == subpage && p.matches(vendor, product))
+			.cloned())
+	}
+
+	/// For log page `page`, subpage `subpage`, returns its name, including the names of vendor-specific pages that match this device.
+	pub fn page_name(&mut self, page: u8, subpage: u8) -> Result<Option<&'static str>, Error> {
+		if let Some(name) = log_catalog::page_name(page, subpage) {
+			return Ok(Some(name));
+		}
+
+		Ok(self.vendor_page(page, subpage)?.map(|p| p.name))
+	}
+
+	/**
+	Interprets `page` as a list of parameters, using vendor-specific decoder if there's one for this page and this device, or [the generic decoder](../data/log_page/struct.Page.html#method.decode) otherwise.
+
+	Returns `None` if page is not a list of parameters.
+	*/
+	pub fn decode_page(&mut self, page: &log_page::Page) -> Result<Option<Vec<log_page::DecodedParameter>>, Error> {
+		if let 0x30 ... 0x3e = page.page {
+			if let Some(vendor_page) = self.vendor_page(page.page, page.subpage.unwrap_or(0))? {
+				return Ok((vendor_page.decode)(page));
+			}
+		}
+
+		Ok(page.decode())
+	}
+
+	/**
+	Returns list of `(page, subpage)` tuples for the log pages that this device supports; pages without subpages are listed with subpage 0.
+
+	Supported Log Pages and Subpages page (00h/FFh) is queried first; if device does not support subpages, Supported Log Pages page (00h) is used instead.
+	*/
+	pub fn supported_pages(&mut self) -> Result<Vec<(u8, u8)>, Error> {
 		if self.supported_pages == None {
-			info!("querying supported log pages");
+			info!("querying supported log pages and subpages");
+
+			let pages = match self.get_page(0x00, 0xff) {
+				// some devices ignore subpage and return plain 00h page instead, so make sure we got what we asked for
+				Ok(ref page) if page.subpage == Some(0xff) => {
+					page.data.chunks(2)
+						.filter(|psp| psp.len() == 2)
+						.map(|psp| (psp[0] & 0b11_1111, psp[1]))
+						.collect()
+				},
+				Ok(_) | Err(Error::SCSI(scsi::Error::Sense(..))) | Err(Error::InvalidData(_)) => {
+					info!("subpages are not supported, querying supported log pages");
 
-			let page = self.get_page(0x00)?;
-			self.supported_pages = Some(page.data.to_vec());
+					let page = self.get_page(0x00, 0x00)?;
+					page.data.iter().map(|p| (p & 0b11_1111, 0)).collect()
+				},
+				Err(err) => return Err(err),
+			};
+			self.supported_pages = Some(pages);
 		} else {
 			// this one repeats way too often
 			//info!("(cached) querying supported log pages");
@@ -169,32 +502,129 @@ impl<'a> SCSIPages<'a, SCSIDevice> {
 		Ok(self.supported_pages.as_ref().unwrap().to_vec())
 	}
 
-	fn get_page(&mut self, page: u8) -> Result<log_page::Page, Error> {
-		// this very function is also used by self.supported_pages() so skip that
-		if page != 0x00 && ! self.supported_pages()?.contains(&page) {
+	/// Tells whether device supports log page `page`, subpage `subpage`.
+	pub fn is_supported(&mut self, page: u8, subpage: u8) -> Result<bool, Error> {
+		Ok(self.supported_pages()?.contains(&(page, subpage)))
+	}
+
+	/**
+	Issues LOG SENSE for page `page`, subpage `subpage` (use 0 for pages without subpages), and returns the page as is.
+
+	Returns `Error::NotSupported` if the device does not list this page as supported.
+	*/
+	pub fn get_page(&mut self, page: u8, subpage: u8) -> Result<log_page::Page, Error> {
+		// these are used by self.supported_pages() so skip them
+		if page != 0x00 && ! self.is_supported(page, subpage)? {
 			// this is a little shortcut function, there is no general need to info!() here (log_sense() would do that for us)
 			// however we want to show whether we aborted early because page is not supported
-			info!("attemted to query unsupported page {}", page);
+			info!("attemted to query unsupported page {:02x}/{:02x}", page, subpage);
 			return Err(Error::NotSupported)
 		}
 
-		let (_sense, data) = self.device.log_sense(
+		let (sense, data) = self.device.log_sense(
 			false, // changed
 			false, // save_params
 			false, // default
 			false, // threshold
-			page, 0, // page, subpage
+			page, subpage,
 			0, // param_ptr
 		)?;
+		scsi::check_sense(&sense)?;
 
 		log_page::parse(&data).ok_or(Error::InvalidData("parse log page data"))
 	}
 
-	fn get_params(&mut self, page: u8) -> Result<Vec<log_page::Parameter>, Error> {
-		let page = self.get_page(page)?;
+	fn get_params(&mut self, page: u8, subpage: u8) -> Result<Vec<log_page::Parameter>, Error> {
+		let page = self.get_page(page, subpage)?;
 		page.parse_params().ok_or(Error::InvalidData("parse log page params"))
 	}
 
+	/**
+	Issues LOG SELECT to reset all the cumulative parameters of the log page `page`, subpage `subpage` to their default values (usually zeroes).
+
+	If `save` is set, reset values are also saved into the non-volatile memory.
+
+	Only SPC-4 (and later) devices allow to reset a single page: older ones ignore page code and reset all the log pages at once, so for them this function returns `Error::NotSupported`.
+	*/
+	pub fn reset_page(&mut self, page: u8, subpage: u8, save: bool) -> Result<(), Error> {
+		info!("resetting log page {:02x}/{:02x}", page, subpage);
+
+		// do not accidentally reset all the pages at once
+		if page == 0x00 || !self.resets_single_page()? {
+			return Err(Error::NotSupported);
+		}
+		if ! self.is_supported(page, subpage)? {
+			return Err(Error::NotSupported);
+		}
+
+		let sense = self.device.log_select(
+			true, // pcr
+			save,
+			false, // default
+			false, // threshold
+			page, subpage,
+			&[],
+		)?;
+		Ok(scsi::check_sense(&sense)?)
+	}
+
+	/// Whether device honours page code of LOG SELECT with PCR bit set, which is only the case since SPC-4; SPC-3 and earlier say this field is reserved, and reset all the pages.
+	fn resets_single_page(&mut self) -> Result<bool, Error> {
+		use scsi::data::inquiry::Version::*;
+		Ok(match self.inquiry()?.2 {
+			SPC4 | SPC5 => true,
+			_ => false,
+		})
+	}
+
+	/**
+	Issues LOG SELECT to set cumulative values of parameters `params` of the log page `page`, subpage `subpage`.
+
+	If `save` is set, values are also saved into the non-volatile memory. Note that devices usually only allow to change few parameters, if any.
+	*/
+	pub fn set_params(&mut self, page: u8, subpage: u8, params: &[log_page::Parameter], save: bool) -> Result<(), Error> {
+		info!("setting parameters of log page {:02x}/{:02x}", page, subpage);
+
+		if ! self.is_supported(page, subpage)? {
+			return Err(Error::NotSupported);
+		}
+
+		let data = log_page::compose(page, if subpage == 0 { None } else { Some(subpage) }, params);
+		let sense = self.device.log_select(
+			false, // pcr
+			save,
+			false, // default
+			false, // threshold
+			page, subpage,
+			&data,
+		)?;
+		Ok(scsi::check_sense(&sense)?)
+	}
+
+	/**
+	Resets Write, Read, Read Reverse and Verify Error Counter pages (02h–05h), and Non-Medium Error page (06h); pages that device does not support are skipped.
+
+	If `save` is set, reset values are also saved into the non-volatile memory.
+
+	Returns `Error::NotSupported` for pre-SPC-4 devices, see [reset_page](#method.reset_page).
+	*/
+	pub fn reset_error_counters(&mut self, save: bool) -> Result<(), Error> {
+		info!("resetting error counters");
+
+		if !self.resets_single_page()? {
+			return Err(Error::NotSupported);
+		}
+
+		for page in 0x02 .. 0x07 {
+			match self.reset_page(page, 0x00, save) {
+				Ok(()) | Err(Error::NotSupported) => (),
+				Err(err) => return Err(err),
+			}
+		}
+
+		Ok(())
+	}
+
 	/**
 	Asks for log page `page` and interprets its contents as a list of error counters
 
@@ -208,7 +638,7 @@ impl<'a> SCSIPages<'a, SCSIDevice> {
 	pub fn error_counters(&mut self, page: u8) -> Result<HashMap<ErrorCounter, u64>, Error> {
 		info!("querying error counters (page {})", page);
 
-		let params = self.get_params(page)?;
+		let params = self.get_params(page, 0)?;
 
 		let counters = params.iter().map(|param| {
 			// XXX tell about unexpected params?
@@ -264,7 +694,7 @@ impl<'a> SCSIPages<'a, SCSIDevice> {
 	pub fn non_medium_error_count(&mut self) -> Result<u64, Error> {
 		info!("querying non-medium error counters");
 
-		let params = self.get_params(0x06)?;
+		let params = self.get_params(0x06, 0)?;
 
 		for param in params {
 			// XXX tell about unexpected params?
@@ -286,7 +716,7 @@ impl<'a> SCSIPages<'a, SCSIDevice> {
 	pub fn temperature(&mut self) -> Result<(Option<u8>, Option<u8>), Error> {
 		info!("querying device temperature");
 
-		let params = self.get_params(0x0d)?;
+		let params = self.get_params(0x0d, 0)?;
 
 		let mut temp = None;
 		let mut ref_temp = None;
@@ -311,11 +741,75 @@ impl<'a> SCSIPages<'a, SCSIDevice> {
 		Ok((temp, ref_temp))
 	}
 
+	/// Environmental Reporting log subpage (0Dh/01h), see SPC-5, 7.3.8.2.
+	pub fn environmental_reporting(&mut self) -> Result<Vec<EnvironmentalReport>, Error> {
+		info!("querying environmental reports");
+
+		let params = self.get_params(0x0d, 0x01)?;
+
+		let reports = params.iter().map(|param| {
+			// XXX tell about unexpected params?
+			if param.value.len() < 8 { return None; }
+
+			let sensor = EnvironmentalSensor::from(param.code);
+			if let EnvironmentalSensor::Reserved(_) = sensor { return None; }
+
+			// value[0] is reserved
+			Some(EnvironmentalReport {
+				sensor: sensor,
+				current: sensor.value(param.value[1]),
+				lifetime_max: sensor.value(param.value[2]),
+				lifetime_min: sensor.value(param.value[3]),
+				max_since_power_on: sensor.value(param.value[4]),
+				min_since_power_on: sensor.value(param.value[5]),
+				max_other: sensor.value(param.value[6]),
+				min_other: sensor.value(param.value[7]),
+			})
+		})
+		.filter(|kv| kv.is_some())
+		.map(|kv| kv.unwrap())
+		.collect();
+
+		Ok(reports)
+	}
+
+	/// Environmental Limits log subpage (0Dh/02h), see SPC-5, 7.3.8.3.
+	pub fn environmental_limits(&mut self) -> Result<Vec<EnvironmentalLimits>, Error> {
+		info!("querying environmental limits");
+
+		let params = self.get_params(0x0d, 0x02)?;
+
+		let limits = params.iter().map(|param| {
+			// XXX tell about unexpected params?
+			if param.value.len() < 8 { return None; }
+
+			let sensor = EnvironmentalSensor::from(param.code);
+			if let EnvironmentalSensor::Reserved(_) = sensor { return None; }
+
+			Some(EnvironmentalLimits {
+				sensor: sensor,
+				high_critical_trigger: sensor.value(param.value[0]),
+				high_critical_reset: sensor.value(param.value[1]),
+				low_critical_reset: sensor.value(param.value[2]),
+				low_critical_trigger: sensor.value(param.value[3]),
+				high_operating_trigger: sensor.value(param.value[4]),
+				high_operating_reset: sensor.value(param.value[5]),
+				low_operating_reset: sensor.value(param.value[6]),
+				low_operating_trigger: sensor.value(param.value[7]),
+			})
+		})
+		.filter(|kv| kv.is_some())
+		.map(|kv| kv.unwrap())
+		.collect();
+
+		Ok(limits)
+	}
+
 	/// In SPC-4, this is called Start-Stop Cycle Counter
 	pub fn dates_and_cycle_counters(&mut self) -> Result<DatesAndCycleCounters, Error> {
 		info!("querying cycle counters");
 
-		let params = self.get_params(0x0e)?;
+		let params = self.get_params(0x0e, 0)?;
 
 		let mut result = DatesAndCycleCounters {
 			manufacturing_date: None,
@@ -332,19 +826,14 @@ impl<'a> SCSIPages<'a, SCSIDevice> {
 					// XXX tell about unexpected params?
 					if param.value.len() < 6 { continue; }
 
-					result.manufacturing_date = Some(Date {
-						year: String::from_utf8(param.value[0..4].to_vec()).unwrap(), // ASCII
-						week: String::from_utf8(param.value[4..6].to_vec()).unwrap(), // ASCII
-					});
+					result.manufacturing_date = Date::parse(&param.value[0..6]);
 				},
 				0x0002 => {
 					// XXX tell about unexpected params?
 					if param.value.len() < 6 { continue; }
 
-					result.accounting_date = Some(Date {
-						year: String::from_utf8(param.value[0..4].to_vec()).unwrap(), // ASCII, might be all-spaces
-						week: String::from_utf8(param.value[4..6].to_vec()).unwrap(), // ASCII, might be all-spaces
-					});
+					// might be all-spaces
+					result.accounting_date = Date::parse(&param.value[0..6]);
 				},
 				0x0003 => {
 					// XXX tell about unexpected params?
@@ -387,10 +876,46 @@ impl<'a> SCSIPages<'a, SCSIDevice> {
 		Ok(result)
 	}
 
+	/// Power Condition Transitions log page (1Ah), see SPC-4, 7.3.11.
+	pub fn power_condition_transitions(&mut self) -> Result<PowerConditionTransitions, Error> {
+		info!("querying power condition transitions");
+
+		let params = self.get_params(0x1a, 0x00)?;
+
+		let mut result = PowerConditionTransitions {
+			active: None,
+			idle_a: None,
+			idle_b: None,
+			idle_c: None,
+			standby_z: None,
+			standby_y: None,
+		};
+
+		for param in params {
+			// XXX tell about unexpected params?
+			if param.value.len() < 4 { continue; }
+
+			let value = Some((&param.value[0..4]).read_u32::<BigEndian>().unwrap());
+			match param.code {
+				0x0001 => { result.active = value },
+				0x0002 => { result.idle_a = value },
+				0x0003 => { result.idle_b = value },
+				0x0004 => { result.idle_c = value },
+				0x0008 => { result.standby_z = value },
+				0x0009 => { result.standby_y = value },
+				_ => {
+					// XXX tell about unexpected params?
+				},
+			}
+		}
+
+		Ok(result)
+	}
+
 	pub fn self_test_results(&mut self) -> Result<Vec<SelfTest>, Error> {
 		info!("querying self-test results");
 
-		let params = self.get_params(0x10)?;
+		let params = self.get_params(0x10, 0)?;
 
 		let self_tests = params.iter().map(|param| {
 			// XXX tell about unexpected params?
@@ -431,7 +956,7 @@ impl<'a> SCSIPages<'a, SCSIDevice> {
 	pub fn informational_exceptions(&mut self) -> Result<Vec<InformationalException>, Error> {
 		info!("querying informational exceptions");
 
-		let params = self.get_params(0x2f)?;
+		let params = self.get_params(0x2f, 0)?;
 
 		let exceptions = params.iter().map(|param| {
 			// XXX tell about unexpected params?
@@ -454,4 +979,360 @@ impl<'a> SCSIPages<'a, SCSIDevice> {
 
 		Ok(exceptions)
 	}
+
+	/// Background Scan Results log page (15h), see SBC-3, 6.4.2.
+	pub fn background_scan(&mut self) -> Result<BackgroundScan, Error> {
+		info!("querying background scan results");
+
+		let params = self.get_params(0x15, 0x00)?;
+
+		let mut status = None;
+		let mut results = vec![];
+
+		for param in params {
+			match param.code {
+				0x0000 => {
+					// XXX tell about unexpected params?
+					if param.value.len() < 10 { continue; }
+
+					use self::BackgroundScanStatus::*;
+					status = Some(BackgroundScan {
+						power_on_minutes: (&param.value[0..4]).read_u32::<BigEndian>().unwrap(),
+						// value[4] is reserved
+						status: match param.value[5] {
+							0x00 => NoScansActive,
+							0x01 => MediumScanActive,
+							0x02 => PreScanActive,
+							0x03 => HaltedFatalError,
+							0x04 => HaltedErrorPattern,
+							0x05 => HaltedNoPList,
+							0x06 => HaltedVendorSpecific,
+							0x07 => HaltedTemperature,
+							0x08 => WaitingForTimer,
+							0x09 => HaltedListFull,
+							0x0a => HaltedPreScanTimeLimit,
+							x => Reserved(x),
+						},
+						scans_performed: (&param.value[6..8]).read_u16::<BigEndian>().unwrap(),
+						progress: (&param.value[8..10]).read_u16::<BigEndian>().unwrap() as f32 * 100. / 65536.,
+						// not present in older devices
+						medium_scans_performed: if param.value.len() < 12 { None } else {
+							Some((&param.value[10..12]).read_u16::<BigEndian>().unwrap())
+						},
+						results: vec![],
+					});
+				},
+				0x0001...0x0800 => {
+					// XXX tell about unexpected params?
+					if param.value.len() < 20 { continue; }
+
+					use self::ReassignStatus::*;
+					results.push(BackgroundScanResult {
+						power_on_minutes: (&param.value[0..4]).read_u32::<BigEndian>().unwrap(),
+						reassign_status: match param.value[4] >> 4 {
+							0x1 => NotReassigned,
+							0x2 => Reassigned,
+							0x4 => ReassignFailed,
+							0x5 => RecoveredInPlace,
+							0x6 => ReassignedByHost,
+							0x7 => ReassignedByHostNoData,
+							0x8 => ReassignByHostFailed,
+							x => Reserved(x),
+						},
+						sense_key: param.value[4] & 0b1111,
+						sense_asc: param.value[5],
+						sense_ascq: param.value[6],
+						vendor_specific: param.value[7..12].to_vec(),
+						lba: (&param.value[12..20]).read_u64::<BigEndian>().unwrap(),
+					});
+				},
+				_ => {
+					// XXX tell about unexpected params?
+				},
+			}
+		}
+
+		let mut status = status.ok_or(Error::InvalidData("find background scan status param in the page"))?;
+		status.results = results;
+
+		Ok(status)
+	}
+
+	/// Pending Defects log subpage (15h/01h), see SBC-4, 6.4.9; these are the LBAs that are waiting to be reassigned.
+	pub fn pending_defects(&mut self) -> Result<PendingDefects, Error> {
+		info!("querying pending defects");
+
+		let params = self.get_params(0x15, 0x01)?;
+
+		let mut count = None;
+		let mut defects = vec![];
+
+		for param in params {
+			match param.code {
+				0x0000 => {
+					// XXX tell about unexpected params?
+					if param.value.len() < 4 { continue; }
+
+					count = Some((&param.value[0..4]).read_u32::<BigEndian>().unwrap());
+				},
+				0x0001...0xf000 => {
+					// XXX tell about unexpected params?
+					if param.value.len() < 12 { continue; }
+
+					defects.push(PendingDefect {
+						power_on_hours: (&param.value[0..4]).read_u32::<BigEndian>().unwrap(),
+						lba: (&param.value[4..12]).read_u64::<BigEndian>().unwrap(),
+					});
+				},
+				_ => {
+					// XXX tell about unexpected params?
+				},
+			}
+		}
+
+		Ok(PendingDefects {
+			// count param is mandatory, but just in case
+			count: count.unwrap_or(defects.len() as u32),
+			defects: defects,
+		})
+	}
+
+	/// Percentage Used Endurance Indicator from the Solid State Media log page (11h), see SBC-3, 6.4.11; values over 100 mean that device went past its rated endurance.
+	pub fn percentage_used_endurance(&mut self) -> Result<u8, Error> {
+		info!("querying percentage used endurance indicator");
+
+		let params = self.get_params(0x11, 0x00)?;
+
+		for param in params {
+			// XXX tell about unexpected params?
+			if param.code != 0x0001 { continue; }
+			if param.value.len() < 4 { continue; }
+
+			// value[0..3] are reserved
+			return Ok(param.value[3]);
+		}
+
+		Err(Error::InvalidData("find valid param in the page"))
+	}
+
+	/// General Statistics and Performance log page (19h), see SPC-4, 7.3.10.
+	pub fn general_statistics(&mut self) -> Result<GeneralStatistics, Error> {
+		info!("querying general statistics and performance");
+
+		let params = self.get_params(0x19, 0x00)?;
+
+		let mut stats = None;
+		let mut idle_intervals = None;
+		let mut time_interval = None;
+
+		for param in params {
+			match param.code {
+				0x0001 => {
+					// XXX tell about unexpected params?
+					if param.value.len() < 64 { continue; }
+
+					let v = |i: usize| (&param.value[i*8 .. i*8 + 8]).read_u64::<BigEndian>().unwrap();
+					stats = Some(GeneralStatistics {
+						read_commands: v(0),
+						write_commands: v(1),
+						blocks_received: v(2),
+						blocks_transmitted: v(3),
+						read_processing_intervals: v(4),
+						write_processing_intervals: v(5),
+						weighted_commands: v(6),
+						weighted_processing_intervals: v(7),
+						idle_intervals: None,
+						time_interval: None,
+					});
+				},
+				0x0002 => {
+					// XXX tell about unexpected params?
+					if param.value.len() < 8 { continue; }
+
+					idle_intervals = Some((&param.value[0..8]).read_u64::<BigEndian>().unwrap());
+				},
+				0x0003 => {
+					// XXX tell about unexpected params?
+					if param.value.len() < 8 { continue; }
+
+					// interval is integer × 10^(-exponent) seconds
+					let exponent = (&param.value[0..4]).read_u32::<BigEndian>().unwrap();
+					let integer = (&param.value[4..8]).read_u32::<BigEndian>().unwrap();
+					time_interval = Some(integer as f64 / 10f64.powi(exponent as i32));
+				},
+				_ => {
+					// XXX tell about unexpected params?
+				},
+			}
+		}
+
+		let mut stats = stats.ok_or(Error::InvalidData("find general access statistics param in the page"))?;
+		stats.idle_intervals = idle_intervals;
+		stats.time_interval = time_interval;
+
+		Ok(stats)
+	}
+
+	/// Utilization log subpage (0Eh/01h), see SBC-4, 6.4.12.
+	pub fn utilization(&mut self) -> Result<Utilization, Error> {
+		info!("querying utilization");
+
+		let params = self.get_params(0x0e, 0x01)?;
+
+		let mut result = Utilization {
+			workload_utilization: None,
+			usage_rate: None,
+		};
+
+		for param in params {
+			match param.code {
+				0x0000 => {
+					// XXX tell about unexpected params?
+					if param.value.len() < 2 { continue; }
+
+					// in 0.01% units
+					result.workload_utilization = Some((&param.value[0..2]).read_u16::<BigEndian>().unwrap() as f32 / 100.);
+				},
+				0x0001 => {
+					// XXX tell about unexpected params?
+					if param.value.is_empty() { continue; }
+
+					result.usage_rate = Some(param.value[0]);
+				},
+				_ => {
+					// XXX tell about unexpected params?
+				},
+			}
+		}
+
+		Ok(result)
+	}
+
+	/**
+	Protocol-Specific Port log page (18h) for SAS devices, see SPL-3, 9.2.8.1.
+
+	Ports of protocols other than SAS are skipped.
+	*/
+	pub fn sas_ports(&mut self) -> Result<Vec<SASPort>, Error> {
+		info!("querying SAS ports");
+
+		let params = self.get_params(0x18, 0x00)?;
+
+		let mut ports = vec![];
+		for param in params {
+			// XXX tell about unexpected params?
+			if param.value.len() < 4 { continue; }
+			// protocol identifier: SAS Serial SCSI Protocol
+			if param.value[0] & 0b1111 != 0x6 { continue; }
+
+			let mut phys = vec![];
+
+			let data = &param.value[4..];
+			let mut current_phy = 0;
+			for _ in 0 .. param.value[3] {
+				if current_phy + 4 > data.len() { break; }
+				let len = data[current_phy + 3] as usize + 4;
+				if current_phy + len > data.len() { break; }
+
+				let phy = &data[current_phy .. current_phy + len];
+				current_phy += len;
+
+				// XXX tell about unexpected descriptors?
+				if phy.len() < 48 { continue; }
+
+				phys.push(SASPhy {
+					// phy[0] is reserved
+					id: phy[1],
+					// phy[2] is reserved, phy[3] is descriptor length
+					attached_device: {
+						use self::AttachedDevice::*;
+						match (phy[4] & 0b111_0000) >> 4 {
+							0 => NoDevice,
+							1 => EndDevice,
+							2 => Expander,
+							3 => FanoutExpander,
+							x => Reserved(x),
+						}
+					},
+					attached_reason: phy[4] & 0b1111,
+					reason: phy[5] >> 4,
+					link_rate: {
+						use self::LinkRate::*;
+						match phy[5] & 0b1111 {
+							0x0 => Unknown,
+							0x1 => Disabled,
+							0x2 => NegotiationFailed,
+							0x3 => SpinupHold,
+							0x4 => PortSelector,
+							0x5 => ResetInProgress,
+							0x6 => UnsupportedPhy,
+							0x8 => Rate(1.5),
+							0x9 => Rate(3.),
+							0xa => Rate(6.),
+							0xb => Rate(12.),
+							0xc => Rate(22.5),
+							x => Reserved(x),
+						}
+					},
+					attached_ssp_initiator: phy[6] & 0b1000 != 0,
+					attached_stp_initiator: phy[6] & 0b100 != 0,
+					attached_smp_initiator: phy[6] & 0b10 != 0,
+					attached_ssp_target: phy[7] & 0b1000 != 0,
+					attached_stp_target: phy[7] & 0b100 != 0,
+					attached_smp_target: phy[7] & 0b10 != 0,
+					sas_address: (&phy[8..16]).read_u64::<BigEndian>().unwrap(),
+					attached_sas_address: (&phy[16..24]).read_u64::<BigEndian>().unwrap(),
+					attached_phy_id: phy[24],
+					// phy[25..32] are reserved
+					invalid_dwords: (&phy[32..36]).read_u32::<BigEndian>().unwrap(),
+					running_disparity_errors: (&phy[36..40]).read_u32::<BigEndian>().unwrap(),
+					loss_of_dword_sync: (&phy[40..44]).read_u32::<BigEndian>().unwrap(),
+					phy_reset_problems: (&phy[44..48]).read_u32::<BigEndian>().unwrap(),
+					// the rest are phy event descriptors
+				});
+			}
+
+			ports.push(SASPort {
+				id: param.code,
+				generation: param.value[2],
+				phys: phys,
+			});
+		}
+
+		Ok(ports)
+	}
+
+	/**
+	Returns `Some((asc, ascq))` describing informational exception (e.g. failure prediction threshold exceeded) that the device currently reports, `Some((0, 0))` if there's none, or `None` if device provides no means to tell.
+
+	Informational Exceptions log page is queried first; if device does not support it, REQUEST SENSE is issued instead.
+	Note that in the latter case only additional sense codes for warnings (0Bh) and failure predictions (5Dh) are considered, as REQUEST SENSE might also report things like low power conditions.
+	*/
+	pub fn informational_exception_code(&mut self) -> Result<Option<(u8, u8)>, Error> {
+		info!("querying informational exception code");
+
+		match self.informational_exceptions() {
+			Ok(ref exceptions) if !exceptions.is_empty() => {
+				return Ok(Some((exceptions[0].asc, exceptions[0].ascq)));
+			},
+			Ok(_) | Err(Error::NotSupported) => (),
+			Err(err) => return Err(err),
+		}
+
+		info!("informational exceptions log page is unavailable, falling back to REQUEST SENSE");
+
+		let (_sense, data) = self.device.request_sense(false)?;
+		if data.is_empty() {
+			return Ok(None);
+		}
+
+		Ok(match sense::parse(&data) {
+			Some((_, sense)) => match sense.code() {
+				(_, 0x00, _) => Some((0, 0)),
+				(_, asc @ 0x0b, ascq) | (_, asc @ 0x5d, ascq) => Some((asc, ascq)),
+				_ => None,
+			},
+			None => None,
+		})
+	}
 }
@@ -0,0 +1,108 @@
+/*!
+Functions to inspect persistent reservations (SCSI-3 PR) of the device.
+
+Commands that cannot be executed because of the reservation held by another initiator fail with [`scsi::Error::Status(Status::ReservationConflict)`](../enum.Error.html#variant.Status).
+
+## Example
+
+```ignore
+use hdd::Device;
+use hdd::scsi::SCSIDevice;
+use hdd::scsi::reservations::Reservations;
+
+...
+
+print!("registered keys: {:?}\n", dev.registered_keys().unwrap().keys);
+print!("reservation: {:?}\n", dev.reservation().unwrap().reservation);
+
+for registrant in dev.full_status().unwrap().registrants {
+	print!("{:016x} {:?}\n", registrant.key, registrant.transport_id);
+}
+```
+*/
+
+use scsi;
+use scsi::{SCSIDevice, SCSICommon};
+use scsi::data::persistent_reserve::{self, Keys, ReservationStatus, Capabilities, FullStatus};
+
+quick_error! {
+	#[derive(Debug)]
+	pub enum Error {
+		NotSupported {}
+		SCSI(err: scsi::Error) {
+			from()
+			display("{}", err)
+		}
+		/// failed to parse command response
+		InvalidData(what: &'static str) {
+			display("Unable to {}", what)
+		}
+	}
+}
+
+fn into_error(err: scsi::Error) -> Error {
+	if err.is_invalid_opcode() || err.is_invalid_field_in_cdb() {
+		Error::NotSupported
+	} else {
+		Error::SCSI(err)
+	}
+}
+
+/// Initial allocation length for the lists of registrants; commands are repeated with larger buffer if the list does not fit
+const ALLOC: u16 = 1024;
+
+/// See [module documentation](index.html).
+pub trait Reservations: SCSICommon {
+	/// Issues PERSISTENT RESERVE IN with `service_action`, repeating it if response does not fit the buffer.
+	fn persistent_reserve_in_data(&self, service_action: u8) -> Result<Vec<u8>, Error> {
+		let query = |alloc| -> Result<Vec<u8>, Error> {
+			let (sense, data) = self.persistent_reserve_in(service_action, alloc)?;
+			scsi::check_sense(&sense).map_err(into_error)?;
+			Ok(data)
+		};
+
+		let data = query(ALLOC)?;
+		match persistent_reserve::response_len(&data) {
+			// list is longer than the buffer
+			Some(len) if len > ALLOC as u32 => query(::std::cmp::min(len, 0xffff) as u16),
+			_ => Ok(data),
+		}
+	}
+
+	/// Returns reservation keys of all the registered initiators.
+	fn registered_keys(&self) -> Result<Keys, Error> {
+		info!("reading persistent reservation keys");
+
+		persistent_reserve::parse_keys(&self.persistent_reserve_in_data(0x00)?)
+			.ok_or(Error::InvalidData("parse READ KEYS response"))
+	}
+
+	/// Returns persistent reservation currently held, if any.
+	fn reservation(&self) -> Result<ReservationStatus, Error> {
+		info!("reading persistent reservation");
+
+		persistent_reserve::parse_reservation(&self.persistent_reserve_in_data(0x01)?)
+			.ok_or(Error::InvalidData("parse READ RESERVATION response"))
+	}
+
+	fn reservation_capabilities(&self) -> Result<Capabilities, Error> {
+		info!("reading persistent reservation capabilities");
+
+		// this one has fixed length, and no generation code
+		let (sense, data) = self.persistent_reserve_in(0x02, 8)?;
+		scsi::check_sense(&sense).map_err(into_error)?;
+
+		persistent_reserve::parse_capabilities(&data)
+			.ok_or(Error::InvalidData("parse REPORT CAPABILITIES response"))
+	}
+
+	/// Returns all the registrants, along with their transport IDs and reservations they hold.
+	fn full_status(&self) -> Result<FullStatus, Error> {
+		info!("reading persistent reservation full status");
+
+		persistent_reserve::parse_full_status(&self.persistent_reserve_in_data(0x03)?)
+			.ok_or(Error::InvalidData("parse READ FULL STATUS response"))
+	}
+}
+
+impl Reservations for SCSIDevice {}
@@ -0,0 +1,104 @@
+/*!
+Retrying of commands that fail for transient reasons, e.g. unit attention after bus reset, or device being busy.
+
+## Example
+
+```ignore
+use std::time::Duration;
+use hdd::Device;
+use hdd::scsi::{SCSIDevice, Status};
+use hdd::scsi::retry::RetryPolicy;
+
+...
+
+let mut dev = SCSIDevice::new(Device::open("/dev/da0").unwrap());
+dev.set_retry_policy(RetryPolicy {
+	max_attempts: 10,
+	backoff: Duration::from_millis(500),
+	.. RetryPolicy::default()
+});
+
+// or, to disable retries altogether
+dev.set_retry_policy(RetryPolicy::none());
+```
+*/
+
+use std::time::Duration;
+use scsi::{Status, TransportError};
+use scsi::data::sense;
+
+/// Describes which failures are worth retrying, and how. See [module documentation](index.html).
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+	/// Maximum number of attempts, including the first one; 1 disables retries
+	pub max_attempts: u32,
+	/// Delay before the first retry; doubles with each subsequent retry
+	pub backoff: Duration,
+	/// Upper limit for the delay between retries
+	pub max_backoff: Duration,
+	/// Statuses (other than CHECK CONDITION) to retry commands on
+	pub statuses: Vec<Status>,
+	/// Current sense data to retry commands on, as tuples of `(sense key, additional sense code, additional sense code qualifier)`; `None` matches any ASC or ASCQ
+	pub sense: Vec<(u8, Option<u8>, Option<u8>)>,
+	/// Host adapter or driver failures to retry commands on
+	pub transport: Vec<TransportError>,
+}
+
+impl Default for RetryPolicy {
+	/// Retries BUSY, TASK SET FULL, UNIT ATTENTION (e.g. after bus reset), NOT READY / LOGICAL UNIT IS IN PROCESS OF BECOMING READY, and host adapter resets, bus busy conditions and driver soft errors up to 5 times, starting with 100ms delay.
+	fn default() -> Self {
+		RetryPolicy {
+			max_attempts: 5,
+			backoff: Duration::from_millis(100),
+			max_backoff: Duration::from_secs(2),
+			statuses: vec![Status::Busy, Status::TaskSetFull],
+			sense: vec![
+				(0x06, None, None),
+				(0x02, Some(0x04), Some(0x01)),
+			],
+			transport: vec![TransportError::Reset, TransportError::BusBusy, TransportError::SoftError],
+		}
+	}
+}
+
+impl RetryPolicy {
+	/// Policy that never retries anything.
+	pub fn none() -> Self {
+		RetryPolicy {
+			max_attempts: 1,
+			backoff: Duration::from_secs(0),
+			max_backoff: Duration::from_secs(0),
+			statuses: vec![],
+			sense: vec![],
+			transport: vec![],
+		}
+	}
+
+	/// Tells whether command that returned `status` and `sense` should be retried (regardless of the number of attempts made so far).
+	pub fn should_retry(&self, status: Status, sense: &[u8]) -> bool {
+		if self.statuses.contains(&status) { return true; }
+		if status != Status::CheckCondition || sense.is_empty() { return false; }
+
+		// deferred errors are not related to the command that returned them, so retrying is pointless
+		let (key, asc, ascq) = match sense::parse(&sense.to_vec()) {
+			Some((true, sense)) => sense.code(),
+			_ => return false,
+		};
+
+		self.sense.iter().any(|&(k, a, q)|
+			k == key
+			&& a.map(|a| a == asc).unwrap_or(true)
+			&& q.map(|q| q == ascq).unwrap_or(true)
+		)
+	}
+
+	/// Returns delay before the retry that follows attempt number `attempt` (starting from 1).
+	pub fn delay(&self, attempt: u32) -> Duration {
+		// avoid overflowing the multiplier; delay is going to hit `max_backoff` long before that anyway
+		let multiplier = 1u32 << ::std::cmp::min(attempt.saturating_sub(1), 16);
+		match self.backoff.checked_mul(multiplier) {
+			Some(delay) => ::std::cmp::min(delay, self.max_backoff),
+			None => self.max_backoff,
+		}
+	}
+}
@@ -0,0 +1,144 @@
+/*!
+Functions to start, abort and track device self-tests
+
+Results of the past self-tests can be found in the Self-Test Results log page (see [`SCSIPages::self_test_results`](../pages/struct.SCSIPages.html#method.self_test_results)).
+
+## Example
+
+```ignore
+use hdd::Device;
+use hdd::scsi::SCSIDevice;
+use hdd::scsi::self_test::{SelfTests, Test};
+
+...
+
+dev.start_self_test(Test::BackgroundShort).unwrap();
+
+while let Some(progress) = dev.self_test_progress().unwrap() {
+	println!("{:.0}% done", progress);
+	thread::sleep(Duration::from_secs(10));
+}
+```
+*/
+
+use scsi;
+use scsi::{SCSIDevice, PageControl};
+use scsi::modes::{self, Modes};
+use scsi::data::sense;
+
+quick_error! {
+	#[derive(Debug)]
+	pub enum Error {
+		SCSI(err: scsi::Error) {
+			from()
+			display("{}", err)
+		}
+		Mode(err: modes::Error) {
+			from()
+			display("{}", err)
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Test {
+	/// Device's default self-test; the command only returns after the test is complete
+	Default,
+	BackgroundShort,
+	BackgroundExtended,
+	/// Short self-test; the command only returns after the test is complete
+	ForegroundShort,
+	/// Extended self-test; the command only returns after the test is complete, which might take hours
+	ForegroundExtended,
+}
+
+/// Timeout for tests that are expected to be done in less than two minutes (SPC-4, 5.14.2)
+const SHORT_TEST_TIMEOUT: u32 = 2 * 60 + 30;
+/// Timeout for extended tests if device does not report how long they take
+const UNKNOWN_TEST_TIMEOUT: u32 = 24 * 60 * 60;
+
+/// See [module documentation](index.html).
+pub trait SelfTests: Modes {
+	/**
+	Starts self-test `test`.
+
+	Note that for foreground tests this function blocks until the test is complete; for extended foreground test, command timeout is derived from the Control mode page (see [extended_self_test_time](#method.extended_self_test_time)).
+	*/
+	fn start_self_test(&self, test: Test) -> Result<(), Error> {
+		info!("starting self-test: {:?}", test);
+
+		let (code, self_test, timeout) = match test {
+			Test::Default => (0b000, true, SHORT_TEST_TIMEOUT),
+			Test::BackgroundShort => (0b001, false, scsi::DEFAULT_TIMEOUT),
+			Test::BackgroundExtended => (0b010, false, scsi::DEFAULT_TIMEOUT),
+			Test::ForegroundShort => (0b101, false, SHORT_TEST_TIMEOUT),
+			Test::ForegroundExtended => {
+				// leave some room for the things like spin-up
+				let timeout = match self.extended_self_test_time()? {
+					Some(t) => (t as u32) * 2 + SHORT_TEST_TIMEOUT,
+					// no Control mode page, or device does not tell; what else is there to do?
+					None => UNKNOWN_TEST_TIMEOUT,
+				};
+				(0b110, false, timeout)
+			},
+		};
+
+		let sense = self.send_diagnostic(code, self_test, &[], timeout)?;
+		Ok(scsi::check_sense(&sense)?)
+	}
+
+	/// Aborts background self-test that is currently in progress.
+	fn abort_self_test(&self) -> Result<(), Error> {
+		info!("aborting self-test");
+
+		let sense = self.send_diagnostic(0b100, false, &[], scsi::DEFAULT_TIMEOUT)?;
+		Ok(scsi::check_sense(&sense)?)
+	}
+
+	/**
+	Returns progress of the background self-test, in percent, or `None` if no self-test is in progress.
+
+	This issues REQUEST SENSE and looks for LOGICAL UNIT NOT READY, SELF-TEST IN PROGRESS, with its progress indication in the sense-key specific data.
+	*/
+	fn self_test_progress(&self) -> Result<Option<f32>, Error> {
+		info!("querying self-test progress");
+
+		let (sense, data) = self.request_sense(false)?;
+		scsi::check_sense(&sense)?;
+
+		if data.is_empty() {
+			return Ok(None);
+		}
+
+		let sense = match sense::parse(&data) {
+			Some((_, sense)) => sense,
+			None => return Ok(None),
+		};
+
+		match sense.code() {
+			(_, 0x04, 0x09) => Ok(Some(
+				sense.sense_key_specific()
+					.and_then(|sks| sks.percent())
+					// self-test is in progress, but device does not tell how far it's gone
+					.unwrap_or(0.)
+			)),
+			_ => Ok(None),
+		}
+	}
+
+	/// Returns recommended time to complete an extended self-test, in seconds (where `0xffff` means 65535 seconds or more), as reported in the Control mode page, or `None` if device does not report it.
+	fn extended_self_test_time(&self) -> Result<Option<u16>, Error> {
+		info!("querying extended self-test completion time");
+
+		match self.control(PageControl::Current) {
+			Ok(control) => Ok(match control.extended_self_test_completion_time {
+				0 => None,
+				x => Some(x),
+			}),
+			Err(modes::Error::NotSupported) => Ok(None),
+			Err(err) => Err(err)?,
+		}
+	}
+}
+
+impl SelfTests for SCSIDevice {}